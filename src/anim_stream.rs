@@ -0,0 +1,132 @@
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TryRecvError};
+use crate::codecs;
+
+/// Frames decoded ahead of playback. Together with the frame on screen and the one being
+/// decoded, this bounds the memory of an animation however many frames it has.
+const READ_AHEAD: usize = 4;
+
+/// Shortest delay between frames; zero delays are clamped the same way browsers do.
+const MIN_DELAY_MS: u32 = 20;
+
+pub struct AnimFrame {
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub delay_ms: u32,
+}
+
+impl std::fmt::Debug for AnimFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnimFrame")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("delay_ms", &self.delay_ms)
+            .finish()
+    }
+}
+
+/// A frame from the decoder thread. `epoch` counts seeks, so frames queued before
+/// a seek can be told apart from those after it.
+#[derive(Debug)]
+pub struct StreamedFrame {
+    pub frame: AnimFrame,
+    pub index: usize,
+    epoch: u32,
+}
+
+pub enum FramePoll {
+    Ready(StreamedFrame),
+    /// The decoder has not caught up yet
+    Pending,
+    /// Single frame, or decoding failed; nothing more will come
+    Ended,
+}
+
+/// An animation decoding on its own thread, looping until dropped.
+#[derive(Debug)]
+pub struct AnimationStream {
+    frames: Receiver<StreamedFrame>,
+    seeks: Sender<usize>,
+    epoch: u32,
+}
+
+impl AnimationStream {
+    /// Start decoding and wait for the first frame. None when not even that decodes.
+    pub fn open(path: PathBuf, data: Vec<u8>) -> Option<(Self, AnimFrame)> {
+        let (frame_sender, frames) = mpsc::sync_channel(READ_AHEAD);
+        let (seeks, seek_receiver) = mpsc::channel();
+        std::thread::spawn(move || decode_loop(path, data, frame_sender, seek_receiver));
+        let first = frames.recv().ok()?;
+        Some((Self { frames, seeks, epoch: 0 }, first.frame))
+    }
+
+    /// The next frame in playback order, if it has been decoded.
+    pub fn poll(&mut self) -> FramePoll {
+        loop {
+            match self.frames.try_recv() {
+                // Queued before the last seek
+                Ok(frame) if frame.epoch != self.epoch => continue,
+                Ok(frame) => return FramePoll::Ready(frame),
+                Err(TryRecvError::Empty) => return FramePoll::Pending,
+                Err(TryRecvError::Disconnected) => return FramePoll::Ended,
+            }
+        }
+    }
+
+    /// Continue from frame `index`. Earlier frames are decoded again, since each
+    /// frame may build on the ones before it.
+    pub fn seek(&mut self, index: usize) {
+        if self.seeks.send(index).is_ok() {
+            self.epoch += 1;
+        }
+    }
+}
+
+fn decode_loop(path: PathBuf, data: Vec<u8>, frames: SyncSender<StreamedFrame>, seeks: Receiver<usize>) {
+    let mut start = 0;
+    let mut epoch = 0;
+    loop {
+        let mut index = 0;
+        let mut seek_to = None;
+        let mut closed = false;
+        let result = codecs::for_each_frame(&path, &data, |frame| {
+            match seeks.try_recv() {
+                Ok(target) => {
+                    seek_to = Some(target);
+                    return false;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    closed = true;
+                    return false;
+                }
+                Err(TryRecvError::Empty) => {}
+            }
+            index += 1;
+            if index <= start {
+                return true;
+            }
+            let (width, height) = frame.image.dimensions();
+            let frame = AnimFrame { rgba: frame.image.into_raw(), width, height, delay_ms: frame.delay_ms.max(MIN_DELAY_MS) };
+            // Blocks while the read-ahead is full; fails once the stream is dropped
+            closed = frames.send(StreamedFrame { frame, index: index - 1, epoch }).is_err();
+            !closed
+        });
+        if let Err(e) = result {
+            eprintln!("Failed to decode animation {}: {}", path.display(), e);
+            return;
+        }
+        if closed {
+            return;
+        }
+        match seek_to {
+            Some(target) => {
+                start = target;
+                epoch += 1;
+            }
+            // A single frame has nothing to loop
+            None if index <= 1 => return,
+            None => start = 0,
+        }
+    }
+}
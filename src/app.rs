@@ -1,17 +1,20 @@
 #![allow(unused_assignments)]
 
 use relm4::prelude::*;
-use crate::utils::is_supported_image;
+use crate::utils::is_image_file;
 use gtk4::prelude::*;
 use gtk4::License;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use dirs;
 
-use crate::components::sidebar::{SidebarModel, SidebarMsg, SidebarOutput, scan_directory_custom};
+use crate::components::sidebar::{SidebarModel, SidebarMsg, SidebarOutput, DirListing, scan_directory_custom};
 use crate::components::image_view::{ImageViewModel, ImageViewMsg, ImageViewOutput};
 use crate::components::settings_dialog::{SettingsDialogModel, SettingsDialogMsg, SettingsDialogOutput};
+use crate::components::password_dialog::{PasswordDialogModel, PasswordDialogMsg, PasswordDialogOutput};
+use crate::page_source::{NameEncoding, PasswordError};
 
-use crate::database::{AppSettings, AppState, DbHelper, SortType, DirectorySettings};
+use crate::database::{AppSettings, AppState, ArchivePassword, DbHelper, SortType, DirectorySettings, FitMode, PageRotation, PageTransform};
 use crate::input_settings::{InputMap, Action};
 use crate::i18n::{localize, Language};
 
@@ -19,6 +22,7 @@ pub struct AppModel {
     sidebar: Controller<SidebarModel>,
     image_view: Controller<ImageViewModel>,
     settings_dialog: Controller<SettingsDialogModel>,
+    password_dialog: Controller<PasswordDialogModel>,
     settings: AppSettings,
     db_helper: Option<DbHelper>,
     current_image: Option<PathBuf>,
@@ -28,6 +32,8 @@ pub struct AppModel {
     current_dir_sort: SortType,
     current_image_sort: SortType,
     spread_view: bool,
+    continuous_view: bool,
+    fit_mode: FitMode,
     right_to_left: bool,
     last_path: Option<String>,
     
@@ -50,6 +56,7 @@ pub enum AppMsg {
     NextPage,
     PrevPage,
     ToggleSpread,
+    ToggleContinuous,
     ZoomIn,
     ZoomOut,
     ResetZoom,
@@ -62,11 +69,20 @@ pub enum AppMsg {
     PathChanged(String),
     DirSortChanged(SortType),
     ImageSortChanged(SortType),
+    NameEncodingChanged(NameEncoding),
     SpreadModeChanged(bool),
+    ContinuousModeChanged(bool),
+    FitModeChanged(FitMode),
+    TransformsChanged { folder: PageTransform, pages: HashMap<PathBuf, PageTransform> },
+    AllPages { pages: Vec<PathBuf>, current: PathBuf },
+    PageInView(PathBuf),
     RTLChanged(bool),
     NextDir,
     PrevDir,
     ClearImage,
+    Prefetch(Vec<PathBuf>),
+    PasswordRequired(PasswordError),
+    PasswordEntered { archive: PathBuf, password: String, remember: bool },
     CursorMotion,
     CheckCursorHide,
     TriggerAction(Action),
@@ -162,11 +178,14 @@ impl SimpleComponent for AppModel {
                 SidebarOutput::OpenImage(path) => AppMsg::OpenImage(path),
                 // SidebarOutput::OpenDirectory(path) => AppMsg::DirSelected(path),
                 SidebarOutput::SpreadPages(paths) => AppMsg::SpreadPages(paths),
+                SidebarOutput::AllPages { pages, current } => AppMsg::AllPages { pages, current },
                 SidebarOutput::PathChanged(p) => AppMsg::PathChanged(p),
                 SidebarOutput::ImageSortChanged(s) => AppMsg::ImageSortChanged(s),
                 SidebarOutput::ClearImage => AppMsg::ClearImage,
                 SidebarOutput::RequestNextDir => AppMsg::NextDir,
                 SidebarOutput::RequestPrevDir => AppMsg::PrevDir,
+                SidebarOutput::Prefetch(paths) => AppMsg::Prefetch(paths),
+                SidebarOutput::PasswordRequired(path) => AppMsg::PasswordRequired(PasswordError::Required(path)),
             });
         let image_view = ImageViewModel::builder()
             .launch(())
@@ -174,8 +193,14 @@ impl SimpleComponent for AppModel {
                 ImageViewOutput::DirSortChanged(s) => AppMsg::DirSortChanged(s),
                 ImageViewOutput::ImageSortChanged(s) => AppMsg::ImageSortChanged(s),
                 ImageViewOutput::SpreadModeChanged(v) => AppMsg::SpreadModeChanged(v),
+                ImageViewOutput::ContinuousModeChanged(v) => AppMsg::ContinuousModeChanged(v),
+                ImageViewOutput::FitModeChanged(m) => AppMsg::FitModeChanged(m),
+                ImageViewOutput::TransformsChanged { folder, pages } => AppMsg::TransformsChanged { folder, pages },
+                ImageViewOutput::PageInView(p) => AppMsg::PageInView(p),
                 ImageViewOutput::RTLChanged(v) => AppMsg::RTLChanged(v),
                 ImageViewOutput::TriggerAction(a) => AppMsg::TriggerAction(a),
+                ImageViewOutput::PasswordRequired(e) => AppMsg::PasswordRequired(e),
+                ImageViewOutput::NameEncodingChanged(e) => AppMsg::NameEncodingChanged(e),
             });
 
         let settings_dialog = SettingsDialogModel::builder()
@@ -185,6 +210,13 @@ impl SimpleComponent for AppModel {
                 SettingsDialogOutput::Close => { AppMsg::NoOp },
             });
 
+        let password_dialog = PasswordDialogModel::builder()
+            .launch(())
+            .forward(sender.input_sender(), |output| match output {
+                PasswordDialogOutput::Submit { archive, password, remember } => AppMsg::PasswordEntered { archive, password, remember },
+                PasswordDialogOutput::Close => AppMsg::NoOp,
+            });
+
         // Menu Model
         // Removed static menu_model init, using get_menu_model instead
         
@@ -267,6 +299,7 @@ impl SimpleComponent for AppModel {
             sidebar,
             image_view,
             settings_dialog,
+            password_dialog,
             settings: settings.clone(),
             db_helper,
             current_image: None, 
@@ -274,6 +307,8 @@ impl SimpleComponent for AppModel {
             current_dir_sort,
             current_image_sort,
             spread_view,
+            continuous_view: false,
+            fit_mode: FitMode::default(),
             right_to_left,
             last_path: app_state.last_path.clone(),
             is_fullscreen: false,
@@ -289,8 +324,8 @@ impl SimpleComponent for AppModel {
             sender.input(AppMsg::OpenPath(path));
         } else if let Some(last_path_str) = &model.last_path {
             let path = PathBuf::from(last_path_str);
-             // Verify it exists, else do nothing
-             if path.exists() {
+             // Verify it exists (or lives in an archive that does), else do nothing
+             if path.exists() || crate::page_source::archive_folder(&path).is_some() {
                  sender.input(AppMsg::OpenPath(path));
              }
         }
@@ -302,6 +337,8 @@ impl SimpleComponent for AppModel {
         // Initialize ImageView with current state
         model.image_view.emit(ImageViewMsg::UpdateSettings {
             spread_mode: model.spread_view,
+            continuous_mode: model.continuous_view,
+            fit_mode: model.fit_mode,
             right_to_left: model.right_to_left,
             dir_sort: model.current_dir_sort,
             image_sort: model.current_image_sort,
@@ -314,6 +351,14 @@ impl SimpleComponent for AppModel {
         model.sidebar.emit(SidebarMsg::UpdateLoopImages(model.settings.loop_images));
         model.sidebar.emit(SidebarMsg::UpdateSingleFirstPage(model.settings.single_first_page));
         model.sidebar.emit(SidebarMsg::UpdateArchivesOnTop(model.settings.archives_on_top));
+        model.sidebar.emit(SidebarMsg::UpdateFlattenArchives(model.settings.flatten_archives));
+        model.apply_prefetch_settings();
+        model.apply_zoom_settings();
+        model.image_view.emit(ImageViewMsg::UpdateAutoPlay(model.settings.auto_play_animations));
+        model.image_view.emit(ImageViewMsg::UpdateColorManagement(model.settings.color_management));
+        model.image_view.emit(ImageViewMsg::UpdateDownscale(model.settings.downscale_large_images));
+        model.image_view.emit(ImageViewMsg::UpdateRotateWholeFolder(model.settings.rotate_whole_folder));
+        model.sidebar.emit(SidebarMsg::SetDbHelper(model.db_helper.clone()));
 
         let widgets = view_output!();
         
@@ -365,14 +410,18 @@ impl SimpleComponent for AppModel {
              AppMsg::OpenImage(path) => {
                 self.current_image = Some(path.clone());
                 self.sidebar.emit(SidebarMsg::SelectImage(path.clone()));
-                if self.spread_view {
-                    self.sidebar.emit(SidebarMsg::GetSpreadPages(path));
-                } else {
-                    self.image_view.emit(ImageViewMsg::ShowPages(vec![path]));
-                }
+                self.show_image(path);
             }
             AppMsg::SpreadPages(paths) => {
-                self.image_view.emit(ImageViewMsg::ShowPages(paths));
+                self.show_pages(paths);
+            }
+            AppMsg::AllPages { pages, current } => {
+                self.image_view.emit(ImageViewMsg::ShowStrip { pages, current });
+            }
+            AppMsg::PageInView(path) => {
+                // Scrolled there in the continuous view: follow it without reopening
+                self.current_image = Some(path.clone());
+                self.sidebar.emit(SidebarMsg::SelectImage(path));
             }
             AppMsg::NextPage => {
                 if let Some(path) = &self.current_image {
@@ -387,12 +436,36 @@ impl SimpleComponent for AppModel {
             AppMsg::ToggleSpread => {
                 _sender.input(AppMsg::SpreadModeChanged(!self.spread_view));
             }
+            AppMsg::ToggleContinuous => {
+                _sender.input(AppMsg::ContinuousModeChanged(!self.continuous_view));
+            }
             AppMsg::ToggleDirection => {
                  _sender.input(AppMsg::RTLChanged(!self.right_to_left));
             }
             AppMsg::SpreadModeChanged(val) => {
                 self.handle_spread_mode_changed(val);
             }
+            AppMsg::ContinuousModeChanged(val) => {
+                self.handle_continuous_mode_changed(val);
+            }
+            AppMsg::FitModeChanged(mode) => {
+                self.fit_mode = mode;
+                if let Some(path) = &self.last_path.clone() {
+                     if let Some(helper) = &self.db_helper {
+                         match helper.get_directory_settings(path) {
+                             Ok(maybe_existing) => {
+                                 let mut ds = maybe_existing.unwrap_or_else(|| self.directory_settings_for(path));
+                                 ds.fit_mode = mode;
+                                 let _ = helper.save_directory_settings(&ds);
+                             }
+                             Err(e) => eprintln!("FitModeChanged: failed to read dir settings: {}", e),
+                         }
+                     }
+                }
+            }
+            AppMsg::TransformsChanged { folder, pages } => {
+                self.handle_transforms_changed(folder, pages);
+            }
             AppMsg::RTLChanged(val) => {
                 self.handle_rtl_changed(val);
             }
@@ -445,13 +518,16 @@ impl SimpleComponent for AppModel {
             AppMsg::OpenPath(path) => {
                 if path.is_dir() {
                      self.sidebar.emit(SidebarMsg::OpenDirectory(path));
-                } else if path.extension().map_or(false, |e| e.eq_ignore_ascii_case("zip")) {
+                } else if crate::page_source::is_container_file(&path) {
                      self.sidebar.emit(SidebarMsg::OpenDirectory(path));
-                } else if is_supported_image(&path) {
+                } else if is_image_file(&path) {
                      if let Some(parent) = path.parent() {
                           self.pending_open_image = Some(path.clone());
                           self.sidebar.emit(SidebarMsg::UpdatePath(parent.to_path_buf()));
                      }
+                } else if crate::page_source::archive_folder(&path).is_some() {
+                     // Folder inside an archive, e.g. restored from the last session
+                     self.sidebar.emit(SidebarMsg::OpenDirectory(path));
                 }
             }
             AppMsg::OpenSettings => {
@@ -463,6 +539,12 @@ impl SimpleComponent for AppModel {
                     if let Err(e) = helper.save_settings(&self.settings) {
                          eprintln!("Failed to save settings: {}", e);
                     }
+                    // Opting out also forgets what was stored before
+                    if !self.settings.remember_archive_passwords {
+                        if let Err(e) = helper.clear_archive_passwords() {
+                            eprintln!("Failed to clear archive passwords: {}", e);
+                        }
+                    }
                 }
                  
                  // Update shared map
@@ -475,9 +557,18 @@ impl SimpleComponent for AppModel {
                     gtk_settings.set_gtk_application_prefer_dark_theme(self.settings.dark_mode);
                 }
                 self.sidebar.emit(SidebarMsg::UpdateArchivesOnTop(self.settings.archives_on_top));
+                self.sidebar.emit(SidebarMsg::UpdateFlattenArchives(self.settings.flatten_archives));
+                self.apply_prefetch_settings();
+                self.apply_zoom_settings();
+                self.image_view.emit(ImageViewMsg::UpdateAutoPlay(self.settings.auto_play_animations));
+                self.image_view.emit(ImageViewMsg::UpdateColorManagement(self.settings.color_management));
+                self.image_view.emit(ImageViewMsg::UpdateDownscale(self.settings.downscale_large_images));
+                self.image_view.emit(ImageViewMsg::UpdateRotateWholeFolder(self.settings.rotate_whole_folder));
                 
                 self.image_view.emit(ImageViewMsg::UpdateSettings {
                     spread_mode: self.settings.default_spread_view,
+                    continuous_mode: self.continuous_view,
+                    fit_mode: self.fit_mode,
                     right_to_left: self.settings.default_right_to_left,
                     dir_sort: self.settings.default_dir_sort,
                     image_sort: self.settings.default_image_sort,
@@ -557,7 +648,7 @@ impl SimpleComponent for AppModel {
                 self.current_dir_sort = sort;
                 if let Some(path_str) = &self.last_path.clone() {
                      if let Some(helper) = &self.db_helper {
-                          let is_archive = path_str.to_lowercase().ends_with(".zip");
+                          let is_archive = crate::page_source::is_container_file(std::path::Path::new(path_str.as_str()));
                           let target_path_str = if is_archive {
                               let p = std::path::Path::new(path_str.as_str());
                               p.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or(path_str.clone())
@@ -569,13 +660,7 @@ impl SimpleComponent for AppModel {
                          // If DB read fails, skip saving to avoid overwriting valid data with defaults.
                          match helper.get_directory_settings(&target_path_str) {
                              Ok(maybe_existing) => {
-                                 let mut ds = maybe_existing.unwrap_or_else(|| DirectorySettings {
-                                     path: target_path_str.clone(),
-                                     spread_view: self.spread_view,
-                                     right_to_left: self.right_to_left,
-                                     dir_sort: self.current_dir_sort,
-                                     image_sort: self.current_image_sort,
-                                 });
+                                 let mut ds = maybe_existing.unwrap_or_else(|| self.directory_settings_for(&target_path_str));
                                  ds.dir_sort = sort;
                                  let _ = helper.save_directory_settings(&ds);
                              }
@@ -591,13 +676,7 @@ impl SimpleComponent for AppModel {
                      if let Some(helper) = &self.db_helper {
                          match helper.get_directory_settings(path) {
                              Ok(maybe_existing) => {
-                                 let mut ds = maybe_existing.unwrap_or_else(|| DirectorySettings {
-                                     path: path.clone(),
-                                     spread_view: self.spread_view,
-                                     right_to_left: self.right_to_left,
-                                     dir_sort: self.current_dir_sort,
-                                     image_sort: self.current_image_sort,
-                                 });
+                                 let mut ds = maybe_existing.unwrap_or_else(|| self.directory_settings_for(path));
                                  ds.image_sort = sort;
                                  let _ = helper.save_directory_settings(&ds);
                              }
@@ -607,6 +686,28 @@ impl SimpleComponent for AppModel {
                 }
                 self.sidebar.emit(SidebarMsg::ChangeImageSort(sort));
             }
+            AppMsg::NameEncodingChanged(encoding) => {
+                // Stored on the archive, not on the folder inside it that happens to be open
+                let archive = self.last_path.as_ref()
+                    .and_then(|p| crate::page_source::archive_folder(std::path::Path::new(p)))
+                    .map(|f| f.container.to_string_lossy().to_string());
+                if let Some(path) = &archive {
+                     if let Some(helper) = &self.db_helper {
+                         match helper.get_directory_settings(path) {
+                             Ok(maybe_existing) => {
+                                 let mut ds = maybe_existing.unwrap_or_else(|| self.directory_settings_for(path));
+                                 ds.name_encoding = encoding;
+                                 let _ = helper.save_directory_settings(&ds);
+                             }
+                             Err(e) => eprintln!("NameEncodingChanged: failed to read dir settings: {}", e),
+                         }
+                     }
+                     // Entry and folder names (and so page paths) change with the encoding:
+                     // reopen the archive from its root
+                     crate::page_source::set_name_encoding(std::path::Path::new(path), encoding);
+                     self.sidebar.emit(SidebarMsg::OpenDirectory(PathBuf::from(path)));
+                }
+            }
             AppMsg::NextDir => {
                 self.handle_request_neighbor_dir(true);
             }
@@ -616,6 +717,40 @@ impl SimpleComponent for AppModel {
             AppMsg::ClearImage => {
                 self.image_view.emit(ImageViewMsg::ShowPages(vec![]));
             }
+            AppMsg::Prefetch(paths) => {
+                self.image_view.emit(ImageViewMsg::Prefetch(paths));
+            }
+            AppMsg::PasswordRequired(err) => {
+                let archive = err.archive().to_path_buf();
+                let incorrect = matches!(err, PasswordError::Incorrect(_));
+                if !incorrect && self.settings.remember_archive_passwords {
+                    let saved = self.db_helper.as_ref()
+                        .and_then(|helper| helper.get_archive_password(&archive.to_string_lossy()).ok().flatten());
+                    if let Some(saved) = saved {
+                        crate::page_source::set_password(&archive, saved.password);
+                        self.reload_current_image();
+                        return;
+                    }
+                }
+                self.password_dialog.emit(PasswordDialogMsg::Open {
+                    archive,
+                    incorrect,
+                    can_remember: self.settings.remember_archive_passwords,
+                    language: self.settings.language,
+                });
+            }
+            AppMsg::PasswordEntered { archive, password, remember } => {
+                if remember && self.settings.remember_archive_passwords {
+                    if let Some(helper) = &self.db_helper {
+                        let entry = ArchivePassword { path: archive.to_string_lossy().to_string(), password: password.clone() };
+                        if let Err(e) = helper.save_archive_password(&entry) {
+                            eprintln!("Failed to save archive password: {}", e);
+                        }
+                    }
+                }
+                crate::page_source::set_password(&archive, password);
+                self.reload_current_image();
+            }
             AppMsg::TriggerAction(action) => {
                  match action {
                     Action::PrevDir => _sender.input(AppMsg::PrevDir),
@@ -627,9 +762,17 @@ impl SimpleComponent for AppModel {
                     Action::ZoomOut => _sender.input(AppMsg::ZoomOut),
                     Action::ResetZoom => _sender.input(AppMsg::ResetZoom),
                     Action::ToggleSpread => _sender.input(AppMsg::ToggleSpread),
+                    Action::ToggleContinuous => _sender.input(AppMsg::ToggleContinuous),
                     Action::ToggleRTL => _sender.input(AppMsg::ToggleDirection),
                     Action::PrevPageSingle => _sender.input(AppMsg::PrevPageSingle),
                     Action::NextPageSingle => _sender.input(AppMsg::NextPageSingle),
+                    Action::ToggleAnimation | Action::NextFrame | Action::PrevFrame
+                    | Action::AnimationFaster | Action::AnimationSlower
+                    | Action::FitPage | Action::FitWidth | Action::FitHeight
+                    | Action::FitOriginal | Action::FitWidthCapped
+                    | Action::SmartNextPage | Action::SmartPrevPage
+                    | Action::RotateClockwise | Action::RotateCounterClockwise | Action::Rotate180
+                    | Action::FlipHorizontal | Action::FlipVertical => self.image_view.emit(ImageViewMsg::TriggerAction(action)),
                 }
             }
             AppMsg::ShowAbout => {
@@ -654,6 +797,46 @@ impl SimpleComponent for AppModel {
 }
 
 impl AppModel {
+    fn show_pages(&self, paths: Vec<PathBuf>) {
+        self.image_view.emit(ImageViewMsg::ShowPages(paths.clone()));
+        self.sidebar.emit(SidebarMsg::PrefetchAround(paths));
+    }
+
+    /// Show `path` in the current view mode: alone, with its spread partner, or within
+    /// the continuous strip of the whole folder.
+    fn show_image(&self, path: PathBuf) {
+        if self.continuous_view {
+            self.sidebar.emit(SidebarMsg::GetAllPages(path));
+        } else if self.spread_view {
+            self.sidebar.emit(SidebarMsg::GetSpreadPages(path));
+        } else {
+            self.show_pages(vec![path]);
+        }
+    }
+
+    /// Show the current page again, e.g. after an archive password was supplied.
+    fn reload_current_image(&self) {
+        if let Some(path) = &self.current_image {
+            self.show_image(path.clone());
+        }
+    }
+
+    fn apply_prefetch_settings(&self) {
+        self.sidebar.emit(SidebarMsg::UpdatePrefetch {
+            ahead: self.settings.prefetch_ahead as usize,
+            behind: self.settings.prefetch_behind as usize,
+        });
+        self.image_view.emit(ImageViewMsg::UpdateCacheBudget(self.settings.prefetch_memory_mb as usize * 1024 * 1024));
+    }
+
+    fn apply_zoom_settings(&self) {
+        self.image_view.emit(ImageViewMsg::UpdateZoomLimits {
+            step: 1.0 + self.settings.zoom_step_percent as f64 / 100.0,
+            min: self.settings.min_zoom_percent as f64 / 100.0,
+            max: self.settings.max_zoom_percent as f64 / 100.0,
+        });
+    }
+
     fn handle_path_changed(&mut self, path_str: String) {
         self.last_path = Some(path_str.clone());
         
@@ -664,22 +847,64 @@ impl AppModel {
             
             // Defaults
             self.spread_view = self.settings.default_spread_view;
+            self.continuous_view = false;
+            self.fit_mode = FitMode::default();
             self.right_to_left = self.settings.default_right_to_left;
             self.current_dir_sort = self.settings.default_dir_sort;
             self.current_image_sort = self.settings.default_image_sort;
 
-            let is_archive = path_str.to_lowercase().ends_with(".zip");
+            let is_archive = crate::page_source::is_container_file(std::path::Path::new(&path_str));
 
-            // Load Image Settings (from Archive or Dir)
+            let mut name_encoding = NameEncoding::Auto;
+            let mut folder_transform = PageTransform::default();
+            let mut page_transforms = HashMap::new();
+
+            // Metadata of the archive (or directory) being read: ComicInfo.xml, or an EPUB's spine direction
+            let container = crate::page_source::archive_folder(std::path::Path::new(&path_str))
+                .map(|f| f.container)
+                .unwrap_or_else(|| PathBuf::from(&path_str));
+            let comic_info = crate::comic_info::load(&container);
+            let declared_rtl = comic_info.as_ref()
+                .and_then(|info| info.right_to_left())
+                .or_else(|| crate::page_source::declared_right_to_left(&container));
+            if let Some(rtl) = declared_rtl {
+                self.right_to_left = rtl;
+            }
+
+            // Load Image Settings (from Archive or Dir). Saved settings win over ComicInfo.
             if let Ok(Some(dir_settings)) = helper.get_directory_settings(&path_str) {
                  self.spread_view = dir_settings.spread_view;
+                 self.continuous_view = dir_settings.continuous_view;
+                 self.fit_mode = dir_settings.fit_mode;
                  self.right_to_left = dir_settings.right_to_left;
                  self.current_image_sort = dir_settings.image_sort;
+                 name_encoding = dir_settings.name_encoding;
+                 folder_transform = dir_settings.transform;
+                 page_transforms = dir_settings.page_transforms.iter()
+                     .map(|page| (std::path::Path::new(&path_str).join(&page.name), page.transform))
+                     .collect();
                  if !is_archive {
                      self.current_dir_sort = dir_settings.dir_sort;
                  }
             }
 
+            // The override belongs to the archive itself, also when browsing one of its folders.
+            // The sidebar has already listed the archive by now; relist if it uses an override.
+            let archive = crate::page_source::archive_folder(std::path::Path::new(&path_str)).map(|f| f.container);
+            let is_zip = archive.as_deref().and_then(crate::page_source::ArchiveKind::from_path) == Some(crate::page_source::ArchiveKind::Zip);
+            if let (true, Some(archive)) = (is_zip, &archive) {
+                if archive.to_string_lossy() != path_str {
+                    if let Ok(Some(archive_settings)) = helper.get_directory_settings(&archive.to_string_lossy()) {
+                        name_encoding = archive_settings.name_encoding;
+                    }
+                }
+                if crate::page_source::name_encoding(archive) != name_encoding {
+                    crate::page_source::set_name_encoding(archive, name_encoding);
+                    self.sidebar.emit(SidebarMsg::Refresh);
+                }
+            }
+            self.image_view.emit(ImageViewMsg::UpdateNameEncoding(is_zip.then_some(name_encoding)));
+
             // Load Directory Sort (from Parent if Archive)
             if is_archive {
                 let path = std::path::Path::new(&path_str);
@@ -694,19 +919,27 @@ impl AppModel {
              // Update UI
              self.image_view.emit(ImageViewMsg::UpdateSettings { 
                 spread_mode: self.spread_view, 
+                continuous_mode: self.continuous_view,
+                fit_mode: self.fit_mode,
                 right_to_left: self.right_to_left,
                 dir_sort: self.current_dir_sort,
                 image_sort: self.current_image_sort,
                 input_map: self.settings.input_map.clone(),
                 language: self.settings.language,
              });
-             self.sidebar.emit(SidebarMsg::UpdateSpreadMode(self.spread_view));
+             self.image_view.emit(ImageViewMsg::UpdateTransforms { folder: folder_transform, pages: page_transforms });
+             self.sidebar.emit(SidebarMsg::UpdateSpreadMode(self.spread_view && !self.continuous_view));
              self.sidebar.emit(SidebarMsg::UpdateDirSort(self.current_dir_sort));
              self.sidebar.emit(SidebarMsg::ChangeImageSort(self.current_image_sort));
+             let double_pages = comic_info.as_ref()
+                 .map(|info| crate::comic_info::page_paths(&container, &info.double_pages()))
+                 .unwrap_or_default();
+             self.sidebar.emit(SidebarMsg::UpdateDoublePages(double_pages));
+             self.image_view.emit(ImageViewMsg::UpdateComicInfo(comic_info.map(Box::new)));
              
              // Check pending image open
              if let Some(pending) = &self.pending_open_image {
-                 self.image_view.emit(ImageViewMsg::ShowPages(vec![pending.clone()]));
+                 self.show_pages(vec![pending.clone()]);
                  self.sidebar.emit(SidebarMsg::OpenImage(pending.clone()));
                  self.current_image = Some(pending.clone());
                  self.pending_open_image = None;
@@ -724,13 +957,7 @@ impl AppModel {
              if let Some(path) = &self.last_path.clone() {
                  match helper.get_directory_settings(path) {
                      Ok(maybe_existing) => {
-                         let mut ds = maybe_existing.unwrap_or_else(|| crate::database::DirectorySettings {
-                             path: path.clone(),
-                             spread_view: self.spread_view,
-                             right_to_left: self.right_to_left,
-                             dir_sort: self.current_dir_sort,
-                             image_sort: self.current_image_sort,
-                         });
+                         let mut ds = maybe_existing.unwrap_or_else(|| self.directory_settings_for(path));
                          ds.spread_view = self.spread_view;
                          let _ = helper.save_directory_settings(&ds);
                      }
@@ -741,36 +968,91 @@ impl AppModel {
         
         self.image_view.emit(ImageViewMsg::UpdateSettings { 
             spread_mode: self.spread_view, 
+            continuous_mode: self.continuous_view,
+            fit_mode: self.fit_mode,
             right_to_left: self.right_to_left,
             dir_sort: self.current_dir_sort,
             image_sort: self.current_image_sort,
             input_map: self.settings.input_map.clone(),
             language: self.settings.language,
         });
-        self.sidebar.emit(SidebarMsg::UpdateSpreadMode(self.spread_view));
+        self.sidebar.emit(SidebarMsg::UpdateSpreadMode(self.spread_view && !self.continuous_view));
         
-         if let Some(path) = &self.current_image {
-            if self.spread_view {
-                self.sidebar.emit(SidebarMsg::GetSpreadPages(path.clone()));
-            } else {
-                self.image_view.emit(ImageViewMsg::ShowPages(vec![path.clone()]));
+        self.reload_current_image();
+    }
+
+    /// Settings for a directory seen for the first time, taken from the current view.
+    fn directory_settings_for(&self, path: &str) -> DirectorySettings {
+        DirectorySettings {
+            spread_view: self.spread_view,
+            right_to_left: self.right_to_left,
+            dir_sort: self.current_dir_sort,
+            image_sort: self.current_image_sort,
+            continuous_view: self.continuous_view,
+            fit_mode: self.fit_mode,
+            ..DirectorySettings::new(path)
+        }
+    }
+
+    /// Remember page rotation and mirroring. Single pages are stored relative to the directory.
+    fn handle_transforms_changed(&self, folder: PageTransform, pages: HashMap<PathBuf, PageTransform>) {
+        let Some(helper) = &self.db_helper else { return; };
+        let Some(path) = self.last_path.clone() else { return; };
+        match helper.get_directory_settings(&path) {
+            Ok(maybe_existing) => {
+                let mut ds = maybe_existing.unwrap_or_else(|| self.directory_settings_for(&path));
+                ds.transform = folder;
+                ds.page_transforms = pages.into_iter()
+                    .filter_map(|(page, transform)| {
+                        let name = page.strip_prefix(&path).ok()?.to_string_lossy().into_owned();
+                        Some(PageRotation { name, transform })
+                    })
+                    .collect();
+                let _ = helper.save_directory_settings(&ds);
             }
+            Err(e) => eprintln!("TransformsChanged: failed to read dir settings: {}", e),
         }
     }
 
+    fn handle_continuous_mode_changed(&mut self, val: bool) {
+        self.continuous_view = val;
+
+        // Save directory settings
+        if let Some(helper) = &self.db_helper {
+             if let Some(path) = &self.last_path.clone() {
+                 match helper.get_directory_settings(path) {
+                     Ok(maybe_existing) => {
+                         let mut ds = maybe_existing.unwrap_or_else(|| self.directory_settings_for(path));
+                         ds.continuous_view = self.continuous_view;
+                         let _ = helper.save_directory_settings(&ds);
+                     }
+                     Err(e) => eprintln!("ContinuousModeChanged: failed to read dir settings: {}", e),
+                 }
+            }
+        }
+
+        self.image_view.emit(ImageViewMsg::UpdateSettings {
+            spread_mode: self.spread_view,
+            continuous_mode: self.continuous_view,
+            fit_mode: self.fit_mode,
+            right_to_left: self.right_to_left,
+            dir_sort: self.current_dir_sort,
+            image_sort: self.current_image_sort,
+            input_map: self.settings.input_map.clone(),
+            language: self.settings.language,
+        });
+        // Page turns in the strip move one page at a time
+        self.sidebar.emit(SidebarMsg::UpdateSpreadMode(self.spread_view && !self.continuous_view));
+        self.reload_current_image();
+    }
+
     fn handle_rtl_changed(&mut self, val: bool) {
         self.right_to_left = val;
         if let Some(helper) = &self.db_helper {
              if let Some(path_str) = &self.last_path.clone() {
                  match helper.get_directory_settings(path_str) {
                      Ok(maybe_existing) => {
-                         let mut ds = maybe_existing.unwrap_or_else(|| crate::database::DirectorySettings {
-                             path: path_str.clone(),
-                             spread_view: self.spread_view,
-                             right_to_left: self.right_to_left,
-                             dir_sort: self.current_dir_sort,
-                             image_sort: self.current_image_sort,
-                         });
+                         let mut ds = maybe_existing.unwrap_or_else(|| self.directory_settings_for(path_str));
                          ds.right_to_left = self.right_to_left;
                          let _ = helper.save_directory_settings(&ds);
                      }
@@ -780,6 +1062,8 @@ impl AppModel {
         }
         self.image_view.emit(ImageViewMsg::UpdateSettings { 
             spread_mode: self.spread_view, 
+            continuous_mode: self.continuous_view,
+            fit_mode: self.fit_mode,
             right_to_left: self.right_to_left,
              dir_sort: self.current_dir_sort,
              image_sort: self.current_image_sort,
@@ -787,13 +1071,7 @@ impl AppModel {
              language: self.settings.language,
         });
         
-        if let Some(path) = &self.current_image {
-            if self.spread_view {
-                self.sidebar.emit(SidebarMsg::GetSpreadPages(path.clone()));
-            } else {
-                self.image_view.emit(ImageViewMsg::ShowPages(vec![path.clone()]));
-            }
-        }
+        self.reload_current_image();
     }
 
 
@@ -801,29 +1079,36 @@ impl AppModel {
         if let Some(current_path_str) = &self.last_path {
             let current_path = PathBuf::from(current_path_str);
             if let Some(target) = self.find_neighbor_directory_recursive(&current_path, is_next) {
+                 let target = self.descend_into_archive_folders(target, is_next);
                  self.sidebar.emit(SidebarMsg::OpenDirectory(target));
             }
         }
     }
-    
-    fn find_neighbor_directory_recursive(&self, current: &PathBuf, is_next: bool) -> Option<PathBuf> {
-         let parent = current.parent()?;
-         let parent_str = parent.to_string_lossy().to_string();
+
+    /// List `path` the way the sidebar would, using the directory sort stored for it.
+    fn scan_for_navigation(&self, path: &PathBuf) -> DirListing {
+         let path_str = path.to_string_lossy().to_string();
          
          // Default settings
          let mut dir_sort = self.settings.default_dir_sort;
          let archives_on_top = self.settings.archives_on_top;
          
-         // Try load settings for parent
+         // Try load settings for the directory
          if let Some(helper) = &self.db_helper {
-             if let Ok(Some(ds)) = helper.get_directory_settings(&parent_str) {
+             if let Ok(Some(ds)) = helper.get_directory_settings(&path_str) {
                  dir_sort = ds.dir_sort;
              }
          }
          
          let image_sort = self.settings.default_image_sort; 
          
-         let (dirs, _) = scan_directory_custom(&parent.to_path_buf(), &dir_sort, &image_sort, archives_on_top);
+         scan_directory_custom(path, &dir_sort, &image_sort, archives_on_top, self.settings.flatten_archives)
+    }
+    
+    fn find_neighbor_directory_recursive(&self, current: &PathBuf, is_next: bool) -> Option<PathBuf> {
+         let parent = current.parent()?;
+         
+         let (dirs, _) = self.scan_for_navigation(&parent.to_path_buf());
          
          let idx = dirs.iter().position(|(_, p, _)| p == current);
          
@@ -842,6 +1127,23 @@ impl AppModel {
          // Recursive step: go up
          self.find_neighbor_directory_recursive(&parent.to_path_buf(), is_next)
     }
+
+    /// An archive whose pages all live in sub-folders has nothing to show at its root;
+    /// land on its first folder (last when going backwards) instead.
+    fn descend_into_archive_folders(&self, mut target: PathBuf, is_next: bool) -> PathBuf {
+        while crate::page_source::archive_folder(&target).is_some() {
+            let (dirs, images) = self.scan_for_navigation(&target);
+            if !images.is_empty() {
+                break;
+            }
+            let folder = if is_next { dirs.first() } else { dirs.last() };
+            match folder {
+                Some((_, path, _)) => target = path.clone(),
+                None => break,
+            }
+        }
+        target
+    }
 }
 
 fn create_menu_model(lang: Language) -> gtk4::gio::Menu {
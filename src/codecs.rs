@@ -0,0 +1,400 @@
+use std::fs::File;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use anyhow::{anyhow, Result};
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::metadata::Orientation;
+use image::{AnimationDecoder, DynamicImage, ImageDecoder, ImageFormat, ImageReader, RgbaImage};
+use jxl_oxide::{EnumColourEncoding, RenderingIntent};
+use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+// Set from the colour management setting; read by the decoding threads.
+static COLOR_MANAGEMENT: AtomicBool = AtomicBool::new(true);
+
+/// Image format, detected from the file content and only then from the extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Anything the `image` crate reads
+    Image(ImageFormat),
+    /// HEIC and AVIF, through libheif
+    Heif,
+    Jxl,
+    /// Photoshop document; only the stored composite is shown
+    Psd,
+}
+
+/// A decoded animation frame and how long it is shown.
+pub struct Frame {
+    pub image: RgbaImage,
+    pub delay_ms: u32,
+}
+
+fn extension(path: &Path) -> String {
+    path.extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase()
+}
+
+/// Format from the leading bytes of a file.
+pub fn sniff(data: &[u8]) -> Option<Format> {
+    // ISO-BMFF: "ftyp" box followed by the major brand
+    if data.len() >= 12 && &data[4..8] == b"ftyp" {
+        if let b"heic" | b"heix" | b"hevc" | b"heim" | b"heis" | b"mif1" | b"msf1" | b"avif" | b"avis" = &data[8..12] {
+            return Some(Format::Heif);
+        }
+    }
+    if data.starts_with(&[0xFF, 0x0A]) || data.starts_with(b"\0\0\0\x0CJXL \r\n\x87\n") {
+        return Some(Format::Jxl);
+    }
+    if data.starts_with(b"8BPS") {
+        return Some(Format::Psd);
+    }
+    image::guess_format(data).ok().map(Format::Image)
+}
+
+fn from_extension(path: &Path) -> Option<Format> {
+    match extension(path).as_str() {
+        "avif" | "heic" | "heif" => Some(Format::Heif),
+        "jxl" => Some(Format::Jxl),
+        "psd" => Some(Format::Psd),
+        ext => ImageFormat::from_extension(ext).map(Format::Image),
+    }
+}
+
+/// Format of `data`, trusting the content over the name. The extension is only
+/// consulted for formats without a signature (TGA).
+pub fn detect(path: &Path, data: &[u8]) -> Option<Format> {
+    sniff(data).or_else(|| from_extension(path))
+}
+
+/// Sniff a file on disk from its first bytes.
+pub fn sniff_file(path: &Path) -> Option<Format> {
+    let mut header = [0u8; 32];
+    let mut file = File::open(path).ok()?;
+    let len = file.read(&mut header).ok()?;
+    sniff(&header[..len])
+}
+
+/// Turn converting embedded ICC profiles to sRGB on or off. Returns true when it changed.
+pub fn set_color_management(enabled: bool) -> bool {
+    COLOR_MANAGEMENT.swap(enabled, Ordering::Relaxed) != enabled
+}
+
+fn color_management() -> bool {
+    COLOR_MANAGEMENT.load(Ordering::Relaxed)
+}
+
+/// True for animated GIF, APNG and WebP, AVIF image sequences and JPEG XL files with more than one frame.
+pub fn is_animated(path: &Path, data: &[u8]) -> bool {
+    match detect(path, data) {
+        Some(Format::Image(ImageFormat::Gif)) => true,
+        Some(Format::Image(ImageFormat::Png)) => crate::utils::is_apng_bytes(data),
+        Some(Format::Image(ImageFormat::WebP)) => crate::utils::is_animated_webp_bytes(data),
+        Some(Format::Heif) => HeifContext::read_from_bytes(data).is_ok_and(|ctx| ctx.has_sequence()),
+        Some(Format::Jxl) => jxl_oxide::JxlImage::read_with_defaults(data)
+            .is_ok_and(|img| img.image_header().metadata.animation.is_some() && img.num_loaded_keyframes() > 1),
+        _ => false,
+    }
+}
+
+/// Decode a still image, or the first frame of an animation.
+pub fn decode(path: &Path, data: &[u8]) -> Result<RgbaImage> {
+    match detect(path, data) {
+        Some(Format::Heif) => decode_heif(data),
+        Some(Format::Jxl) => {
+            let mut first = None;
+            jxl_frames(data, |frame| {
+                first = Some(frame.image);
+                false
+            })?;
+            first.ok_or_else(|| anyhow!("JPEG XL file has no frames"))
+        }
+        Some(Format::Psd) => {
+            let psd = psd::Psd::from_bytes(data).map_err(|e| anyhow!(e))?;
+            let mut rgba = RgbaImage::from_raw(psd.width(), psd.height(), psd.rgba()).ok_or_else(|| anyhow!("PSD composite size mismatch"))?;
+            manage_colors(&mut rgba, psd_icc_profile(data));
+            Ok(rgba)
+        }
+        Some(Format::Image(format)) => {
+            let mut decoder = ImageReader::with_format(Cursor::new(data), format).into_decoder()?;
+            let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+            let icc = if color_management() { decoder.icc_profile().ok().flatten() } else { None };
+            let mut image = DynamicImage::from_decoder(decoder)?;
+            image.apply_orientation(orientation);
+            let mut rgba = image.into_rgba8();
+            manage_colors(&mut rgba, icc.as_deref());
+            Ok(rgba)
+        }
+        None => Ok(image::load_from_memory(data)?.into_rgba8()),
+    }
+}
+
+/// Decode the frames of an animation in order, handing each to `frame` until it returns
+/// false. Frames are composited as they come, so only the current one is held in memory.
+pub fn for_each_frame(path: &Path, data: &[u8], mut frame: impl FnMut(Frame) -> bool) -> Result<()> {
+    let cursor = Cursor::new(data);
+    let frames = match detect(path, data) {
+        Some(Format::Image(ImageFormat::Gif)) => GifDecoder::new(cursor)?.into_frames(),
+        // APNG dispose and blend ops are applied by the decoder
+        Some(Format::Image(ImageFormat::Png)) => PngDecoder::new(cursor)?.apng()?.into_frames(),
+        Some(Format::Image(ImageFormat::WebP)) => WebPDecoder::new(cursor)?.into_frames(),
+        Some(Format::Heif) => return heif_frames(data, frame),
+        Some(Format::Jxl) => return jxl_frames(data, frame),
+        _ => return Err(anyhow!("Not an animated format: {}", path.display())),
+    };
+    for decoded in frames {
+        let decoded = decoded?;
+        let (numer, denom) = decoded.delay().numer_denom_ms();
+        let delay_ms = numer.checked_div(denom).unwrap_or(100);
+        if !frame(Frame { image: decoded.into_buffer(), delay_ms }) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// How many times an animation plays before stopping on its last frame; None loops forever.
+/// Read from the APNG `acTL` chunk, the GIF NETSCAPE2.0 extension, the WebP `ANIM` chunk
+/// or the JPEG XL animation header. AVIF sequences always loop.
+pub fn play_count(path: &Path, data: &[u8]) -> Option<u32> {
+    let plays = match detect(path, data)? {
+        Format::Image(ImageFormat::Png) => {
+            let chunk = png_chunk(data, b"acTL")?;
+            u32::from_be_bytes(chunk.get(4..8)?.try_into().ok()?)
+        }
+        Format::Image(ImageFormat::Gif) => {
+            // Without the extension a GIF plays once
+            let Some(start) = data.windows(11).position(|w| w == b"NETSCAPE2.0") else { return Some(1); };
+            let block = data.get(start + 11..start + 15)?;
+            if block[0] != 3 || block[1] != 1 {
+                return None;
+            }
+            u16::from_le_bytes([block[2], block[3]]) as u32
+        }
+        Format::Image(ImageFormat::WebP) => {
+            let chunk = riff_chunk(data, b"ANIM")?;
+            u16::from_le_bytes(chunk.get(4..6)?.try_into().ok()?) as u32
+        }
+        Format::Jxl => {
+            let img = jxl_oxide::JxlImage::read_with_defaults(data).ok()?;
+            img.image_header().metadata.animation.as_ref()?.num_loops
+        }
+        _ => return None,
+    };
+    // Zero means forever in every format
+    (plays > 0).then_some(plays)
+}
+
+/// Data of the first PNG chunk of the given type before the image data.
+fn png_chunk<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut offset = 8;
+    while offset + 8 <= data.len() {
+        let length = u32::from_be_bytes(data[offset..offset + 4].try_into().ok()?) as usize;
+        let chunk_type = &data[offset + 4..offset + 8];
+        if chunk_type == kind {
+            return data.get(offset + 8..offset + 8 + length);
+        }
+        if chunk_type == b"IDAT" {
+            return None;
+        }
+        offset += 12 + length;
+    }
+    None
+}
+
+/// Data of the first top-level chunk of the given type in a RIFF (WebP) file.
+fn riff_chunk<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let length = u32::from_le_bytes(data[offset + 4..offset + 8].try_into().ok()?) as usize;
+        if &data[offset..offset + 4] == kind {
+            return data.get(offset + 8..offset + 8 + length);
+        }
+        // Chunks are padded to an even size
+        offset += 8 + length + (length & 1);
+    }
+    None
+}
+
+/// Image size from the header, without decoding pixels.
+pub fn dimensions(path: &Path, data: &[u8]) -> Option<(u32, u32)> {
+    match detect(path, data)? {
+        Format::Heif => {
+            let handle = HeifContext::read_from_bytes(data).ok()?.primary_image_handle().ok()?;
+            Some((handle.width(), handle.height()))
+        }
+        Format::Jxl => {
+            let img = jxl_oxide::JxlImage::read_with_defaults(data).ok()?;
+            Some((img.width(), img.height()))
+        }
+        Format::Psd => {
+            // File header: signature, version, reserved, channels, then height and width
+            let height = u32::from_be_bytes(data.get(14..18)?.try_into().ok()?);
+            let width = u32::from_be_bytes(data.get(18..22)?.try_into().ok()?);
+            Some((width, height))
+        }
+        Format::Image(format) => ImageReader::with_format(Cursor::new(data), format).into_decoder().ok().map(displayed_size),
+    }
+}
+
+/// Shrink an image to fit within `(width, height)`, keeping its aspect ratio.
+pub fn fit_within(image: RgbaImage, (max_w, max_h): (u32, u32)) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    if (width <= max_w && height <= max_h) || max_w == 0 || max_h == 0 {
+        return image;
+    }
+    let scale = (max_w as f64 / width as f64).min(max_h as f64 / height as f64);
+    let new_w = ((width as f64 * scale).round() as u32).max(1);
+    let new_h = ((height as f64 * scale).round() as u32).max(1);
+    image::imageops::resize(&image, new_w, new_h, image::imageops::FilterType::Triangle)
+}
+
+/// Width and height as shown, swapped for images the EXIF orientation turns sideways.
+pub fn displayed_size(mut decoder: impl ImageDecoder) -> (u32, u32) {
+    let (width, height) = decoder.dimensions();
+    match decoder.orientation() {
+        Ok(Orientation::Rotate90 | Orientation::Rotate270 | Orientation::Rotate90FlipH | Orientation::Rotate270FlipH) => (height, width),
+        _ => (width, height),
+    }
+}
+
+/// Convert pixels to sRGB from the embedded profile, if colour management is on and
+/// the profile is not sRGB already.
+fn manage_colors(rgba: &mut RgbaImage, icc: Option<&[u8]>) {
+    if let Some(icc) = icc.filter(|icc| color_management() && !is_srgb_profile(icc)) {
+        convert_to_srgb(rgba, icc);
+    }
+}
+
+/// Convert pixels from the colour space of an ICC profile to sRGB. Profiles that cannot
+/// be applied to RGB data (CMYK, grey) leave the pixels as they are.
+fn convert_to_srgb(rgba: &mut RgbaImage, icc: &[u8]) {
+    let Some(input) = qcms::Profile::new_from_slice(icc, false) else { return; };
+    let mut output = qcms::Profile::new_sRGB();
+    output.precache_output_transform();
+    if let Some(transform) = qcms::Transform::new(&input, &output, qcms::DataType::RGBA8, qcms::Intent::Perceptual) {
+        transform.apply(rgba);
+    }
+}
+
+// Most embedded profiles are plain sRGB, which needs no conversion. Recognized by the
+// profile description, in the ASCII (v2) or UTF-16 (v4) form.
+fn is_srgb_profile(icc: &[u8]) -> bool {
+    let Some(count) = icc.get(128..132).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize) else { return false; };
+    let description = (0..count)
+        .filter_map(|i| icc.get(132 + i * 12..144 + i * 12))
+        .find(|tag| &tag[0..4] == b"desc")
+        .and_then(|tag| {
+            let offset = u32::from_be_bytes([tag[4], tag[5], tag[6], tag[7]]) as usize;
+            let size = u32::from_be_bytes([tag[8], tag[9], tag[10], tag[11]]) as usize;
+            icc.get(offset..offset + size)
+        });
+    description.is_some_and(|d| d.windows(4).any(|w| w == b"sRGB") || d.windows(8).any(|w| w == b"\0s\0R\0G\0B"))
+}
+
+/// The ICC profile resource (ID 1039) of a Photoshop document, which the psd crate
+/// does not expose.
+fn psd_icc_profile(data: &[u8]) -> Option<&[u8]> {
+    let read_u32 = |at: usize| data.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize);
+    // 26 byte file header, then the colour mode data and image resources sections
+    let resources_at = 26 + 4 + read_u32(26)?;
+    let end = resources_at + 4 + read_u32(resources_at)?;
+    let mut at = resources_at + 4;
+    while at + 12 <= end {
+        if data.get(at..at + 4)? != b"8BIM" {
+            return None;
+        }
+        let id = u16::from_be_bytes(data.get(at + 4..at + 6)?.try_into().ok()?);
+        // Pascal string name, padded to an even length
+        let name_len = *data.get(at + 6)? as usize;
+        let size_at = at + 6 + ((name_len + 2) & !1);
+        let size = read_u32(size_at)?;
+        let content = size_at + 4;
+        if id == 1039 {
+            return data.get(content..content + size);
+        }
+        at = content + ((size + 1) & !1);
+    }
+    None
+}
+
+fn decode_heif(data: &[u8]) -> Result<RgbaImage> {
+    let ctx = HeifContext::read_from_bytes(data)?;
+    let handle = ctx.primary_image_handle()?;
+    let image = LibHeif::new().decode(&handle, ColorSpace::Rgb(RgbChroma::Rgba), None)?;
+    let mut rgba = heif_to_rgba(&image)?;
+    // HEIC photos are often Display P3
+    if color_management() {
+        let icc = handle.color_profile_raw().or_else(|| image.color_profile_raw());
+        manage_colors(&mut rgba, icc.as_ref().map(|profile| profile.data.as_slice()));
+    }
+    Ok(rgba)
+}
+
+fn heif_frames(data: &[u8], mut frame: impl FnMut(Frame) -> bool) -> Result<()> {
+    let ctx = HeifContext::read_from_bytes(data)?;
+    let track = ctx.track(0).ok_or_else(|| anyhow!("AVIF sequence has no visual track"))?;
+    let timescale = track.timescale().max(1);
+    // The decoder reports the end of the sequence as an error
+    while let Ok(image) = track.decode_next_image(ColorSpace::Rgb(RgbChroma::Rgba), None) {
+        let delay_ms = (image.duration() as u64 * 1000 / timescale as u64) as u32;
+        let mut rgba = heif_to_rgba(&image)?;
+        if color_management() {
+            manage_colors(&mut rgba, image.color_profile_raw().as_ref().map(|profile| profile.data.as_slice()));
+        }
+        if !frame(Frame { image: rgba, delay_ms }) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn heif_to_rgba(image: &libheif_rs::Image) -> Result<RgbaImage> {
+    let planes = image.planes();
+    let plane = planes.interleaved.ok_or_else(|| anyhow!("HEIF image has no interleaved plane"))?;
+    let row_bytes = plane.width as usize * 4;
+    let mut rgba = Vec::with_capacity(row_bytes * plane.height as usize);
+    for row in plane.data.chunks(plane.stride).take(plane.height as usize) {
+        rgba.extend_from_slice(&row[..row_bytes]);
+    }
+    RgbaImage::from_raw(plane.width, plane.height, rgba).ok_or_else(|| anyhow!("HEIF plane size mismatch"))
+}
+
+fn jxl_frames(data: &[u8], mut frame: impl FnMut(Frame) -> bool) -> Result<()> {
+    let mut img = jxl_oxide::JxlImage::read_with_defaults(data).map_err(|e| anyhow!(e))?;
+    // Colour spaces given as an enum are converted by the decoder itself; embedded
+    // ICC profiles need a CMS, so those frames are converted like other formats.
+    let mut icc = None;
+    if color_management() {
+        if img.original_icc().is_some() {
+            icc = Some(img.rendered_icc());
+        } else {
+            img.request_color_encoding(EnumColourEncoding::srgb(RenderingIntent::Perceptual));
+        }
+    }
+    // Durations are counted in ticks of the animation header
+    let tick_ms = img.image_header().metadata.animation.as_ref()
+        .filter(|a| a.tps_numerator > 0)
+        .map_or(0.0, |a| 1000.0 * a.tps_denominator as f64 / a.tps_numerator as f64);
+    for index in 0..img.num_loaded_keyframes() {
+        let render = img.render_frame(index).map_err(|e| anyhow!(e))?;
+        let mut stream = render.stream();
+        let (width, height, channels) = (stream.width(), stream.height(), stream.channels() as usize);
+        let mut samples = vec![0u8; width as usize * height as usize * channels];
+        stream.write_to_buffer(&mut samples);
+        let rgba: Vec<u8> = match channels {
+            1 => samples.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+            2 => samples.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+            3 => samples.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+            _ => samples.chunks_exact(channels).flat_map(|p| [p[0], p[1], p[2], p[3]]).collect(),
+        };
+        let mut image = RgbaImage::from_raw(width, height, rgba).ok_or_else(|| anyhow!("JPEG XL frame size mismatch"))?;
+        manage_colors(&mut image, icc.as_deref());
+        let delay_ms = (render.duration() as f64 * tick_ms) as u32;
+        if !frame(Frame { image, delay_ms }) {
+            break;
+        }
+    }
+    Ok(())
+}
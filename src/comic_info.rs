@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use crate::i18n::{Language, localize};
+use crate::page_source::{self, ArchiveKind, PageSource};
+use crate::utils::is_supported_image;
+
+/// Reading direction hint from the `Manga` element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Manga {
+    #[default]
+    Unknown,
+    No,
+    Yes,
+    YesAndRightToLeft,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ComicPageInfo {
+    /// Index into the container's images in file name order.
+    pub image: usize,
+    pub double_page: bool,
+}
+
+/// Metadata from a `ComicInfo.xml` (ComicRack schema). Only the fields we use or show are kept.
+#[derive(Debug, Clone, Default)]
+pub struct ComicInfo {
+    pub title: Option<String>,
+    pub series: Option<String>,
+    pub number: Option<String>,
+    pub volume: Option<String>,
+    pub summary: Option<String>,
+    pub year: Option<String>,
+    pub writer: Option<String>,
+    pub penciller: Option<String>,
+    pub publisher: Option<String>,
+    pub genre: Option<String>,
+    pub manga: Manga,
+    pub pages: Vec<ComicPageInfo>,
+}
+
+impl ComicInfo {
+    pub fn parse(xml: &str) -> Result<Self> {
+        let doc = roxmltree::Document::parse(xml)?;
+        let root = doc.root_element();
+        let text = |tag: &str| {
+            root.children()
+                .find(|n| n.has_tag_name(tag))
+                .and_then(|n| n.text())
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+        };
+
+        let manga = match text("Manga").as_deref() {
+            Some("YesAndRightToLeft") => Manga::YesAndRightToLeft,
+            Some("Yes") => Manga::Yes,
+            Some("No") => Manga::No,
+            _ => Manga::Unknown,
+        };
+
+        let pages = root.children()
+            .find(|n| n.has_tag_name("Pages"))
+            .map(|pages| {
+                pages.children()
+                    .filter(|n| n.has_tag_name("Page"))
+                    .filter_map(|n| {
+                        Some(ComicPageInfo {
+                            image: n.attribute("Image")?.trim().parse().ok()?,
+                            double_page: n.attribute("DoublePage").is_some_and(|v| v.eq_ignore_ascii_case("true")),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            title: text("Title"),
+            series: text("Series"),
+            number: text("Number"),
+            volume: text("Volume"),
+            summary: text("Summary"),
+            year: text("Year"),
+            writer: text("Writer"),
+            penciller: text("Penciller"),
+            publisher: text("Publisher"),
+            genre: text("Genre"),
+            manga,
+            pages,
+        })
+    }
+
+    /// Indices of pages flagged as double page spreads.
+    pub fn double_pages(&self) -> Vec<usize> {
+        self.pages.iter().filter(|p| p.double_page).map(|p| p.image).collect()
+    }
+
+    /// Initial reading direction implied by the `Manga` element, if any.
+    pub fn right_to_left(&self) -> Option<bool> {
+        match self.manga {
+            Manga::YesAndRightToLeft => Some(true),
+            Manga::No => Some(false),
+            Manga::Yes | Manga::Unknown => None,
+        }
+    }
+
+    /// Human readable summary for the info panel.
+    pub fn describe(&self, lang: Language) -> String {
+        let fields = [
+            ("Title", &self.title),
+            ("Series", &self.series),
+            ("Volume", &self.volume),
+            ("Number", &self.number),
+            ("Writer", &self.writer),
+            ("Penciller", &self.penciller),
+            ("Publisher", &self.publisher),
+            ("Year", &self.year),
+            ("Genre", &self.genre),
+        ];
+        let mut lines: Vec<String> = fields.iter()
+            .filter_map(|(label, value)| value.as_ref().map(|v| format!("{}: {}", localize(label, lang), v)))
+            .collect();
+        if let Some(summary) = &self.summary {
+            lines.push(String::new());
+            lines.push(summary.clone());
+        }
+        lines.join("\n")
+    }
+}
+
+/// Upper bound on remembered containers; the cache starts over when it is exceeded.
+const MAX_CACHED: usize = 4096;
+
+// Container path -> (modification time when read, parsed info). Sorting a directory by
+// series reads every archive in it, so results are kept for as long as the file is unchanged.
+type CacheEntry = (Option<SystemTime>, Option<ComicInfo>);
+static CACHE: Lazy<Mutex<HashMap<PathBuf, CacheEntry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Load `ComicInfo.xml` from the root of a directory or archive.
+pub fn load(container: &Path) -> Option<ComicInfo> {
+    load_cached(container, true)
+}
+
+/// Like [`load`], for listings that look at many containers at once: only directories and
+/// ZIP-based archives on disk are read, each through a handle of its own, so the archive
+/// being read keeps its place among the shared open ones. Everything else gives None.
+pub fn load_for_listing(container: &Path) -> Option<ComicInfo> {
+    let cheap = container.is_dir()
+        || container.is_file() && matches!(ArchiveKind::from_path(container), Some(ArchiveKind::Zip | ArchiveKind::Epub));
+    if !cheap {
+        return None;
+    }
+    load_cached(container, false)
+}
+
+fn load_cached(container: &Path, shared: bool) -> Option<ComicInfo> {
+    let modified = container.ancestors()
+        .find(|p| p.exists())
+        .and_then(|p| std::fs::metadata(p).ok())
+        .and_then(|m| m.modified().ok());
+    if let Some((stamp, info)) = CACHE.lock().unwrap_or_else(|e| e.into_inner()).get(container) {
+        if *stamp == modified {
+            return info.clone();
+        }
+    }
+
+    let info = read(container, shared);
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if cache.len() >= MAX_CACHED && !cache.contains_key(container) {
+        cache.clear();
+    }
+    cache.insert(container.to_path_buf(), (modified, info.clone()));
+    info
+}
+
+fn read(container: &Path, shared: bool) -> Option<ComicInfo> {
+    let find = |source: &mut dyn PageSource| -> Result<Option<ComicInfo>> {
+        let entries = source.entries()?;
+        let Some(entry) = entries.iter().find(|e| e.name.eq_ignore_ascii_case("ComicInfo.xml")) else {
+            return Ok(None);
+        };
+        let data = source.read(&entry.name)?;
+        Ok(Some(ComicInfo::parse(&String::from_utf8_lossy(&data))?))
+    };
+    let result = if shared {
+        page_source::with_source(container, find)
+    } else {
+        page_source::open(container).and_then(|mut source| find(source.as_mut()))
+    };
+    match result {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("Failed to read ComicInfo.xml in {}: {}", container.display(), e);
+            None
+        }
+    }
+}
+
+/// Paths of the pages a ComicInfo `Image` index refers to: the container's images in
+/// natural file name order, as written by the usual tagging tools.
+pub fn page_paths(container: &Path, indices: &[usize]) -> Vec<PathBuf> {
+    if indices.is_empty() {
+        return Vec::new();
+    }
+    let Ok(entries) = page_source::with_source(container, |source| source.entries()) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries.into_iter()
+        .map(|e| e.name)
+        .filter(|n| is_supported_image(Path::new(n)))
+        .collect();
+    names.sort_by(|a, b| natord::compare(a, b));
+    indices.iter().filter_map(|&i| names.get(i)).map(|n| container.join(n)).collect()
+}
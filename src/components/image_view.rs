@@ -1,31 +1,125 @@
 use relm4::prelude::*;
 use gtk4::prelude::*;
 use std::path::PathBuf;
-use crate::database::SortType;
+use crate::database::{FitMode, PageTransform, SortType};
+use crate::oriented_paintable::orient;
 use crate::input_settings::{InputMap, Action, ScrollDirection};
 use crate::i18n::Language;
+use crate::texture_cache::TextureCache;
+use crate::page_source::{NameEncoding, PasswordError};
+use crate::comic_info::ComicInfo;
+use crate::anim_stream::{AnimFrame, AnimationStream, FramePoll};
 
-pub struct AnimFrame {
+/// An animation on screen, fed frame by frame from its decoder thread.
+#[derive(Debug)]
+struct Animation {
+    stream: AnimationStream,
+    // Number of times to play through; None loops forever
+    plays: Option<u32>,
+    // Index and delay of the frame on screen. The total is known once playback wraps around.
+    current: usize,
+    delay_ms: u32,
+    frame_count: Option<usize>,
+    play: u32,
+    finished: bool,
+    // Waiting for a stepped or sought frame, which is shown even while paused
+    stepping: bool,
+    seeking: bool,
+    // Bumped to invalidate the pending frame timer (pause, stepping)
+    timer_token: u32,
+    // Rotation and mirroring of the page it plays on
+    transform: PageTransform,
+}
+
+/// Playback speed limits, as multiples of the stored frame delays.
+const MIN_ANIM_SPEED: f64 = 0.25;
+const MAX_ANIM_SPEED: f64 = 4.0;
+/// How soon to look again when the decoder has not caught up with playback.
+const ANIM_POLL: std::time::Duration = std::time::Duration::from_millis(10);
+
+/// A still image decoded to RGBA off the main thread.
+pub struct DecodedImage {
     pub rgba: Vec<u8>,
     pub width: u32,
     pub height: u32,
-    pub delay_ms: u32,
 }
 
-impl std::fmt::Debug for AnimFrame {
+impl std::fmt::Debug for DecodedImage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("AnimFrame")
+        f.debug_struct("DecodedImage")
             .field("width", &self.width)
             .field("height", &self.height)
-            .field("delay_ms", &self.delay_ms)
             .finish()
     }
 }
 
+/// Upper bound for rendering PDF pages beyond the fit-to-window size.
+const MAX_PDF_ZOOM_FACTOR: f64 = 4.0;
+
+/// Threads decoding the pages to show. Two let a new page start while a stale one finishes.
+const LOAD_WORKERS: usize = 2;
+
+/// Decode box for downscaled pages, as a multiple of the viewport.
+const DOWNSCALE_HEADROOM: f64 = 2.0;
+
+/// Share of the viewport scrolled per smart page turn step; the rest stays in view for context.
+const SMART_SCROLL_STEP: f64 = 0.9;
+
+/// Where newly shown pages are scrolled to, in reading order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PageCorner {
+    // Top left, or top right for right-to-left
+    Start,
+    // Bottom right, or bottom left for right-to-left
+    End,
+}
+
+/// Pages loaded beyond the visible part of the continuous view, and the distance at which
+/// loaded pages are dropped again.
+const STRIP_LOAD_MARGIN: usize = 2;
+const STRIP_KEEP_MARGIN: usize = 6;
+
+/// Pages of the continuous scroll view, stacked top to bottom at the viewport width.
+/// Only pages near the visible part hold a texture.
+#[derive(Debug, Default)]
+struct Strip {
+    pages: Vec<PathBuf>,
+    pictures: Vec<gtk4::Picture>,
+    // Pixel size of each page, from its texture or the dimension cache
+    sizes: Vec<Option<(u32, u32)>>,
+    shown: std::collections::HashSet<usize>,
+    // Requested from the loaders and not arrived yet
+    pending: std::collections::HashSet<usize>,
+    // Page under the top of the view
+    current: usize,
+    // Loads from this generation on belong to the strip
+    first_generation: u32,
+    // Page to bring into view once the layout has caught up with the page heights
+    scroll_target: Option<usize>,
+}
+
+/// Pages for one `load_pages` call.
+struct LoadJob {
+    paths: Vec<PathBuf>,
+    generation: u32,
+    decode_box: Option<(u32, u32)>,
+}
+
+/// Pages to read ahead, decoded to the same box as the pages on screen.
+struct PrefetchRequest {
+    paths: Vec<PathBuf>,
+    decode_box: Option<(u32, u32)>,
+}
+
 #[derive(Debug)]
 pub enum LoadedImageSource {
+    // Data only GDK could make sense of; decoded on the main thread as a last resort
     TextureBytes(Vec<u8>),
-    AnimFrames(Vec<AnimFrame>),
+    // Decoded (and possibly downscaled) on a loader thread
+    Decoded(DecodedImage),
+    // Decoding continues on the stream's own thread; plays is None for endless loops
+    Animation { stream: AnimationStream, first: AnimFrame, plays: Option<u32> },
+    Locked(PasswordError),
     Error,
 }
 
@@ -36,12 +130,33 @@ pub struct ImageViewModel {
     textures_odd: Vec<gtk4::gdk::Paintable>,
     zoom: f64,
     is_fit_to_window: bool,
+    // Kept across page turns; zoom follows it until the user zooms by hand
+    fit_mode: FitMode,
+    manual_zoom: bool,
+    // Zoom factor per wheel notch or key press, and the zoom range
+    zoom_step: f64,
+    min_zoom: f64,
+    max_zoom: f64,
+    // Rotation and mirroring of the directory, pages turned on their own, and which of
+    // the two the rotate and flip actions change
+    folder_transform: PageTransform,
+    page_transforms: std::collections::HashMap<PathBuf, PageTransform>,
+    rotate_whole_folder: bool,
+    // Zoom and pinch centre when a touchpad pinch began
+    pinch: Option<(f64, Option<(f64, f64)>)>,
+    // Corner the next pages are scrolled to once they are on screen
+    open_at: Option<PageCorner>,
+    // Set when a smart page turn goes back, so that the previous page opens at its end
+    open_at_end: bool,
+    // Scrolled windows of the even and odd pages
+    page_windows: Vec<gtk4::ScrolledWindow>,
     pub spread_mode: bool,
+    pub continuous_mode: bool,
     pub right_to_left: bool,
     pub dir_sort: SortType,
     pub image_sort: SortType,
     pub is_fullscreen: bool,
-    anim_data: std::collections::HashMap<(usize, u32), Vec<(gtk4::gdk::Texture, u32)>>,
+    anim_data: std::collections::HashMap<(usize, u32), Animation>,
     pub input_map: InputMap,
     pub language: Language,
     generation: u32,
@@ -52,6 +167,33 @@ pub struct ImageViewModel {
     img_sort_dropdown: Option<gtk4::DropDown>,
     programmatic_dir_change: std::rc::Rc<std::cell::Cell<bool>>,
     programmatic_img_change: std::rc::Rc<std::cell::Cell<bool>>,
+    fit_mode_dropdown: Option<gtk4::DropDown>,
+    programmatic_fit_change: std::rc::Rc<std::cell::Cell<bool>>,
+    // None when the current container is not a ZIP archive (selector hidden)
+    name_encoding: Option<NameEncoding>,
+    name_encoding_dropdown: Option<gtk4::DropDown>,
+    programmatic_encoding_change: std::rc::Rc<std::cell::Cell<bool>>,
+    // ComicInfo.xml of the open archive or directory, shown in the info popover
+    comic_info: Option<Box<ComicInfo>>,
+    texture_cache: TextureCache,
+    // Latest read-ahead request for the prefetch worker; newer requests supersede older ones.
+    prefetch_sender: std::sync::mpsc::Sender<PrefetchRequest>,
+    // Pages to show go to a fixed pool of loader threads, which skip requests older than this
+    load_sender: std::sync::mpsc::Sender<LoadJob>,
+    latest_generation: std::sync::Arc<std::sync::atomic::AtomicU32>,
+    downscale_large_images: bool,
+    // Set when zooming needs the shown pages at their stored size
+    full_resolution: bool,
+    error_message: Option<String>,
+    // Width of the first page before it was reloaded at a higher resolution, to keep the zoomed size steady
+    rescale_from: Option<f64>,
+    // Animation playback, shared by every animated page on screen
+    auto_play: bool,
+    anim_paused: bool,
+    anim_speed: f64,
+    strip: Strip,
+    strip_box: Option<gtk4::Box>,
+    strip_window: Option<gtk4::ScrolledWindow>,
 }
 
 #[derive(Debug)]
@@ -60,27 +202,53 @@ pub enum ImageViewMsg {
     ZoomIn,
     ZoomOut,
     ResetZoom,
-    UpdateSettings { spread_mode: bool, right_to_left: bool, dir_sort: SortType, image_sort: SortType, input_map: InputMap, language: Language },
+    UpdateSettings { spread_mode: bool, continuous_mode: bool, fit_mode: FitMode, right_to_left: bool, dir_sort: SortType, image_sort: SortType, input_map: InputMap, language: Language },
     ChangeDirSort(SortType),
     ChangeImageSort(SortType),
+    ChangeFitMode(FitMode),
+    UpdateNameEncoding(Option<NameEncoding>),
+    UpdateComicInfo(Option<Box<ComicInfo>>),
+    ChangeNameEncoding(NameEncoding),
     ToggleSpread,
+    ToggleContinuous,
     ToggleDirection,
     UpdateFullscreen(bool),
-    AdvanceAnimFrame { slot_index: usize, generation: u32, next_frame_index: usize },
+    AdvanceAnimFrame { slot_index: usize, generation: u32, token: u32 },
     TriggerAction(Action),
     MouseInput { button: u32, modifiers: u32, n_press: i32 },
-    ScrollInput { dy: f64, modifiers: u32 },
+    ScrollInput { dy: f64, modifiers: u32, position: Option<(f64, f64)> },
+    PinchBegin(Option<(f64, f64)>),
+    PinchZoom(f64),
+    PinchEnd,
+    UpdateZoomLimits { step: f64, min: f64, max: f64 },
+    UpdateTransforms { folder: PageTransform, pages: std::collections::HashMap<PathBuf, PageTransform> },
+    UpdateRotateWholeFolder(bool),
     ImageLoaded { index: usize, source: LoadedImageSource, path: PathBuf, generation: u32 },
     ViewportResized(f64, f64),
+    Prefetch(Vec<PathBuf>),
+    PrefetchLoaded { path: PathBuf, image: DecodedImage },
+    UpdateCacheBudget(usize),
+    UpdateAutoPlay(bool),
+    UpdateColorManagement(bool),
+    UpdateDownscale(bool),
+    ShowStrip { pages: Vec<PathBuf>, current: PathBuf },
+    StripScrolled,
 }
 
 #[derive(Debug)]
 pub enum ImageViewOutput {
     DirSortChanged(SortType),
     ImageSortChanged(SortType),
+    NameEncodingChanged(NameEncoding),
     SpreadModeChanged(bool),
+    ContinuousModeChanged(bool),
+    FitModeChanged(FitMode),
+    TransformsChanged { folder: PageTransform, pages: std::collections::HashMap<PathBuf, PageTransform> },
+    // The page under the top of the continuous view changed
+    PageInView(PathBuf),
     RTLChanged(bool),
     TriggerAction(Action),
+    PasswordRequired(PasswordError),
 }
 
 #[relm4::component(pub)]
@@ -125,6 +293,21 @@ impl SimpleComponent for ImageViewModel {
                         }
                     },
                     
+                    gtk4::Button {
+                        #[watch]
+                        set_tooltip_text: Some(&crate::i18n::localize("Toggle Continuous Scroll", model.language)),
+                        set_focusable: false,
+                        #[wrap(Some)]
+                        set_child: continuous_icon = &gtk4::Image {
+                            #[watch]
+                            set_paintable: (if model.continuous_mode { crate::icon::continuous_on() } else { crate::icon::continuous_off() }).as_ref(),
+                            set_pixel_size: 24,
+                        },
+                        connect_clicked[sender] => move |_| {
+                            sender.input(ImageViewMsg::ToggleContinuous);
+                        }
+                    },
+
                     gtk4::Button {
                         #[watch]
                         set_tooltip_text: Some(&crate::i18n::localize("Toggle Right-to-Left", model.language)),
@@ -149,7 +332,7 @@ impl SimpleComponent for ImageViewModel {
                     #[name(dir_sort_dropdown)]
                     gtk4::DropDown {
                         set_model: Some(&gtk4::StringList::new(&[
-                            "Name Asc", "Name Desc", "Date Asc", "Date Desc", "Size Asc", "Size Desc"
+                            "Name Asc", "Name Desc", "Date Asc", "Date Desc", "Size Asc", "Size Desc", "Series Asc", "Series Desc"
                         ])),
                         set_focusable: false,
                     },
@@ -165,6 +348,55 @@ impl SimpleComponent for ImageViewModel {
                         ])),
                         set_focusable: false,
                     },
+
+                    gtk4::Label {
+                        set_label: "Fit:",
+                    },
+
+                    #[name(fit_mode_dropdown)]
+                    gtk4::DropDown {
+                        set_model: Some(&gtk4::StringList::new(
+                            &FitMode::variants().iter().map(|m| m.label()).collect::<Vec<_>>()
+                        )),
+                        set_focusable: false,
+                    },
+
+                    gtk4::Label {
+                        set_label: "Names:",
+                        #[watch]
+                        set_visible: model.name_encoding.is_some(),
+                    },
+
+                    #[name(name_encoding_dropdown)]
+                    gtk4::DropDown {
+                        set_model: Some(&gtk4::StringList::new(
+                            &NameEncoding::variants().iter().map(|e| e.label()).collect::<Vec<_>>()
+                        )),
+                        set_focusable: false,
+                        #[watch]
+                        set_visible: model.name_encoding.is_some(),
+                    },
+
+                    gtk4::MenuButton {
+                        set_icon_name: "dialog-information-symbolic",
+                        set_focusable: false,
+                        #[watch]
+                        set_tooltip_text: Some(&crate::i18n::localize("Comic Info", model.language)),
+                        #[watch]
+                        set_visible: model.comic_info.is_some(),
+                        #[wrap(Some)]
+                        set_popover = &gtk4::Popover {
+                            gtk4::Label {
+                                #[watch]
+                                set_label: &model.comic_info.as_ref().map(|info| info.describe(model.language)).unwrap_or_default(),
+                                set_wrap: true,
+                                set_max_width_chars: 50,
+                                set_xalign: 0.0,
+                                set_selectable: true,
+                                set_margin_all: 5,
+                            },
+                        },
+                    },
                 },
             },
 
@@ -173,12 +405,30 @@ impl SimpleComponent for ImageViewModel {
                 set_hexpand: true,
                 set_vexpand: true,
                 add_css_class: "image-view-background", 
+
+                gtk4::Label {
+                    #[watch]
+                    set_label: model.error_message.as_deref().unwrap_or(""),
+                    #[watch]
+                    set_visible: model.error_message.is_some(),
+                    set_margin_all: 10,
+                    add_css_class: "error",
+                },
+
+                gtk4::Label {
+                    #[watch]
+                    set_label: &model.anim_status().unwrap_or_default(),
+                    #[watch]
+                    set_visible: model.anim_status().is_some(),
+                    set_margin_all: 5,
+                    add_css_class: "dim-label",
+                },
             
                 #[name(main_stack)]
                 gtk4::Stack {
                     set_transition_type: gtk4::StackTransitionType::None,
                     #[watch]
-                    set_visible_child_name: if model.visible_generation % 2 == 0 { "even" } else { "odd" },
+                    set_visible_child_name: if model.continuous_mode { "strip" } else if model.visible_generation % 2 == 0 { "even" } else { "odd" },
                     
                     add_named[Some("even")] = &gtk4::ScrolledWindow {
                         set_hexpand: true,
@@ -199,13 +449,30 @@ impl SimpleComponent for ImageViewModel {
 
                         add_controller = gtk4::EventControllerScroll {
                             set_flags: gtk4::EventControllerScrollFlags::VERTICAL,
-                            connect_scroll[sender] => move |controller, _dx, dy| {
+                            connect_scroll[sender, pointer] => move |controller, _dx, dy| {
                                  let modifiers = controller.current_event_state().bits();
-                                 sender.input(ImageViewMsg::ScrollInput { dy, modifiers }); 
+                                 sender.input(ImageViewMsg::ScrollInput { dy, modifiers, position: pointer.get() });
                                  gtk4::glib::Propagation::Stop
                             }
                         },
 
+                        add_controller = gtk4::EventControllerMotion {
+                            connect_motion[pointer] => move |_, x, y| pointer.set(Some((x, y))),
+                            connect_leave[pointer] => move |_| pointer.set(None),
+                        },
+
+                        add_controller = gtk4::GestureZoom {
+                            connect_begin[sender] => move |gesture, _| {
+                                sender.input(ImageViewMsg::PinchBegin(gesture.bounding_box_center()));
+                            },
+                            connect_scale_changed[sender] => move |_, scale| {
+                                sender.input(ImageViewMsg::PinchZoom(scale));
+                            },
+                            connect_end[sender] => move |_, _| {
+                                sender.input(ImageViewMsg::PinchEnd);
+                            },
+                        },
+
                         add_controller = gtk4::GestureDrag {
                             set_button: gtk4::gdk::BUTTON_PRIMARY,
                             connect_drag_begin[drag_state] => move |gesture, _, _| {
@@ -358,13 +625,30 @@ impl SimpleComponent for ImageViewModel {
 
                         add_controller = gtk4::EventControllerScroll {
                             set_flags: gtk4::EventControllerScrollFlags::VERTICAL,
-                            connect_scroll[sender] => move |controller, _dx, dy| {
+                            connect_scroll[sender, pointer] => move |controller, _dx, dy| {
                                  let modifiers = controller.current_event_state().bits();
-                                 sender.input(ImageViewMsg::ScrollInput { dy, modifiers }); 
+                                 sender.input(ImageViewMsg::ScrollInput { dy, modifiers, position: pointer.get() });
                                  gtk4::glib::Propagation::Stop
                             }
                         },
 
+                        add_controller = gtk4::EventControllerMotion {
+                            connect_motion[pointer] => move |_, x, y| pointer.set(Some((x, y))),
+                            connect_leave[pointer] => move |_| pointer.set(None),
+                        },
+
+                        add_controller = gtk4::GestureZoom {
+                            connect_begin[sender] => move |gesture, _| {
+                                sender.input(ImageViewMsg::PinchBegin(gesture.bounding_box_center()));
+                            },
+                            connect_scale_changed[sender] => move |_, scale| {
+                                sender.input(ImageViewMsg::PinchZoom(scale));
+                            },
+                            connect_end[sender] => move |_, _| {
+                                sender.input(ImageViewMsg::PinchEnd);
+                            },
+                        },
+
                         add_controller = gtk4::GestureDrag {
                             set_button: gtk4::gdk::BUTTON_PRIMARY,
                             connect_drag_begin[drag_state_odd] => move |gesture, _, _| {
@@ -506,13 +790,32 @@ impl SimpleComponent for ImageViewModel {
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
+        let (prefetch_sender, prefetch_receiver) = std::sync::mpsc::channel::<PrefetchRequest>();
+        spawn_prefetch_worker(prefetch_receiver, sender.input_sender().clone());
+        let (load_sender, load_receiver) = std::sync::mpsc::channel::<LoadJob>();
+        let latest_generation = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+        spawn_load_workers(load_receiver, latest_generation.clone(), sender.input_sender().clone());
+
         let mut model = ImageViewModel {
             current_paths: Vec::new(),
             textures_even: Vec::new(),
             textures_odd: Vec::new(),
             zoom: 1.0,
             is_fit_to_window: true,
+            fit_mode: FitMode::default(),
+            manual_zoom: false,
+            zoom_step: 1.05,
+            min_zoom: 0.01,
+            max_zoom: 32.0,
+            folder_transform: PageTransform::default(),
+            page_transforms: std::collections::HashMap::new(),
+            rotate_whole_folder: false,
+            pinch: None,
+            open_at: None,
+            open_at_end: false,
+            page_windows: Vec::new(),
             spread_mode: false,
+            continuous_mode: false,
             right_to_left: true,
             dir_sort: SortType::NameAsc,
             image_sort: SortType::NameAsc,
@@ -527,8 +830,31 @@ impl SimpleComponent for ImageViewModel {
             img_sort_dropdown: None,
             programmatic_dir_change: std::rc::Rc::new(std::cell::Cell::new(false)),
             programmatic_img_change: std::rc::Rc::new(std::cell::Cell::new(false)),
+            fit_mode_dropdown: None,
+            programmatic_fit_change: std::rc::Rc::new(std::cell::Cell::new(false)),
+            name_encoding: None,
+            name_encoding_dropdown: None,
+            comic_info: None,
+            programmatic_encoding_change: std::rc::Rc::new(std::cell::Cell::new(false)),
+            texture_cache: TextureCache::new(0),
+            prefetch_sender,
+            load_sender,
+            latest_generation,
+            downscale_large_images: false,
+            full_resolution: false,
+            error_message: None,
+            rescale_from: None,
+            auto_play: true,
+            anim_paused: false,
+            anim_speed: 1.0,
+            strip: Strip::default(),
+            strip_box: None,
+            strip_window: None,
         };
         
+        // Pointer position over the page view, for zooming around it with the wheel
+        let pointer = std::rc::Rc::new(std::cell::Cell::new(None::<(f64, f64)>));
+
         let drag_state = std::rc::Rc::new(std::cell::RefCell::new((0.0, 0.0)));
         let drag_state_2 = drag_state.clone();
         
@@ -551,6 +877,8 @@ impl SimpleComponent for ImageViewModel {
                     3 => SortType::DateDesc,
                     4 => SortType::SizeAsc,
                     5 => SortType::SizeDesc,
+                    6 => SortType::SeriesAsc,
+                    7 => SortType::SeriesDesc,
                     _ => SortType::NameAsc,
                 };
                 sender_dd.input(ImageViewMsg::ChangeDirSort(sort));
@@ -585,6 +913,73 @@ impl SimpleComponent for ImageViewModel {
             });
             model.img_sort_dropdown = Some(widgets.img_sort_dropdown.clone());
         }
+        {
+            let flag = model.programmatic_fit_change.clone();
+            let sender_dd = sender.clone();
+            widgets.fit_mode_dropdown.connect_selected_notify(move |dd| {
+                if flag.get() { return; }
+                let mode = FitMode::variants().get(dd.selected() as usize).copied().unwrap_or_default();
+                sender_dd.input(ImageViewMsg::ChangeFitMode(mode));
+                if let Some(root) = dd.root() {
+                    if let Ok(window) = root.downcast::<gtk4::Window>() {
+                        gtk4::prelude::GtkWindowExt::set_focus(&window, None::<&gtk4::Widget>);
+                    }
+                }
+            });
+            model.fit_mode_dropdown = Some(widgets.fit_mode_dropdown.clone());
+        }
+        {
+            let flag = model.programmatic_encoding_change.clone();
+            let sender_dd = sender.clone();
+            widgets.name_encoding_dropdown.connect_selected_notify(move |dd| {
+                if flag.get() { return; }
+                let encoding = NameEncoding::variants().get(dd.selected() as usize).copied().unwrap_or_default();
+                sender_dd.input(ImageViewMsg::ChangeNameEncoding(encoding));
+                if let Some(root) = dd.root() {
+                    if let Ok(window) = root.downcast::<gtk4::Window>() {
+                        gtk4::prelude::GtkWindowExt::set_focus(&window, None::<&gtk4::Widget>);
+                    }
+                }
+            });
+            model.name_encoding_dropdown = Some(widgets.name_encoding_dropdown.clone());
+        }
+
+        // Continuous view. Its pictures are created per page, so it is built here rather than in view!.
+        {
+            let strip_box = gtk4::Box::new(gtk4::Orientation::Vertical, 0);
+            let sw = gtk4::ScrolledWindow::builder()
+                .hexpand(true)
+                .vexpand(true)
+                .hscrollbar_policy(gtk4::PolicyType::Never)
+                .child(&strip_box)
+                .build();
+
+            // Mouse bindings as in the paged view; scrolling is left to the scrolled window
+            let click = gtk4::GestureClick::new();
+            click.set_button(0);
+            let sender_click = sender.clone();
+            click.connect_pressed(move |gesture, n_press, _, _| {
+                let button = gesture.current_button();
+                let modifiers = gesture.current_event_state().bits();
+                sender_click.input(ImageViewMsg::MouseInput { button, modifiers, n_press });
+            });
+            sw.add_controller(click);
+
+            let sender_scroll = sender.clone();
+            sw.vadjustment().connect_value_changed(move |_| sender_scroll.input(ImageViewMsg::StripScrolled));
+            // Upper bound and page size follow the layout
+            let sender_layout = sender.clone();
+            sw.vadjustment().connect_changed(move |_| sender_layout.input(ImageViewMsg::StripScrolled));
+
+            widgets.main_stack.add_named(&sw, Some("strip"));
+            model.strip_box = Some(strip_box);
+            model.strip_window = Some(sw);
+        }
+
+        model.page_windows = ["even", "odd"].iter()
+            .filter_map(|name| widgets.main_stack.child_by_name(name))
+            .filter_map(|child| child.downcast::<gtk4::ScrolledWindow>().ok())
+            .collect();
 
         {
             let sender = sender.clone();
@@ -620,128 +1015,31 @@ impl SimpleComponent for ImageViewModel {
     fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
         match msg {
                ImageViewMsg::ShowPages(paths) => {
-                   self.current_paths = paths.clone();
-                   self.is_fit_to_window = true;
-                   
-                   // Increment generation for new request
-                   self.generation += 1;
-                   let current_gen = self.generation;
-                   
-                   // Clear the buffer we are about to load into (the one NOT visible ideally, but here determined by generation)
-                   // If visible_generation == generation - 1, then visible_generation % 2 != generation % 2
-                   // So we are safe to clear generation % 2
-                   let is_even = current_gen % 2 == 0;
-                   if is_even {
-                       self.textures_even.clear();
-                   } else {
-                       self.textures_odd.clear();
-                   }
-                   // Remove anim_data for generations no longer displayed
-                   let vg = self.visible_generation;
-                   self.anim_data.retain(|(_, g), _| *g == vg);
-                   
-                   if paths.is_empty() {
-                       self.visible_generation = current_gen;
+                   // The continuous view gets its pages from ShowStrip
+                   if self.continuous_mode {
+                       if paths.is_empty() {
+                           self.clear_strip();
+                       }
                        return;
                    }
-
-                   let sender_clone = _sender.clone();
-                   let paths_clone = paths.clone();
-
-                   std::thread::spawn(move || {
-                       for (index, path) in paths_clone.iter().enumerate() {
-                            let mut found_source = LoadedImageSource::Error;
-                            
-                            if path.exists() {
-                                // ... loading logic ...
-                                let is_anim = path.extension().and_then(|s| s.to_str()).map_or(false, |ext| {
-                                    let ext = ext.to_lowercase();
-                                    if ext == "gif" || ext == "apng" { return true; }
-                                    if ext == "webp" { return crate::utils::is_animated_webp(path); }
-                                    if ext == "png" { return crate::utils::is_apng(path); }
-                                    false
-                                });
-
-                                if let Ok(data) = std::fs::read(path) {
-                                    if is_anim {
-                                        let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
-                                        let frames = decode_anim_frames(&data, &ext);
-                                        if !frames.is_empty() {
-                                            found_source = LoadedImageSource::AnimFrames(frames);
-                                        } else {
-                                            found_source = LoadedImageSource::TextureBytes(data);
-                                        }
-                                    } else {
-                                        found_source = LoadedImageSource::TextureBytes(data);
-                                    }
-                                }
-                            }
-
-                            if matches!(found_source, LoadedImageSource::Error) {
-                                // Zip logic ...
-                                // (Omitting full Zip logic copy for brevity if unchanged, but I must provide full replacement? 
-                                //  Wait, multi_replace replaces chunk. I should keep the ZIP logic or copy it.
-                                //  Since I can't put "..." in implementation, I have to copy it.
-                                //  Fortunately, the ZIP logic inside thread is stateless regarding self.
-                                //  So it matches the existing logic.)
-                                
-                                let mut zip_found = false;
-                                let mut check_path = path.clone();
-                                while let Some(parent) = check_path.parent() {
-                                     if parent.is_file() {
-                                         if let Some(ext) = parent.extension().and_then(|s| s.to_str()) {
-                                             if ext.to_lowercase() == "zip" {
-                                                 if let Ok(suffix) = path.strip_prefix(parent) {
-                                                     let entry_name = suffix.to_string_lossy();
-                                                     if let Ok(file) = std::fs::File::open(parent) {
-                                                         if let Ok(mut archive) = zip::ZipArchive::new(file) {
-                                                             if let Ok(mut entry) = archive.by_name(&entry_name) {
-                                                                 use std::io::Read;
-                                                                 let mut buffer = Vec::new();
-                                                                 if entry.read_to_end(&mut buffer).is_ok() {
-                                                                     let is_anim = path.extension().and_then(|s| s.to_str()).map_or(false, |ext| {
-                                                                         let ext = ext.to_lowercase();
-                                                                         if ext == "gif" || ext == "apng" { return true; }
-                                                                         if ext == "webp" { return crate::utils::is_animated_webp_bytes(&buffer); }
-                                                                         if ext == "png" { return crate::utils::is_apng_bytes(&buffer); }
-                                                                         false
-                                                                     });
-
-                                                                     if is_anim {
-                                                                         let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
-                                                                         let frames = decode_anim_frames(&buffer, &ext);
-                                                                         if !frames.is_empty() {
-                                                                             found_source = LoadedImageSource::AnimFrames(frames);
-                                                                         } else {
-                                                                             found_source = LoadedImageSource::TextureBytes(buffer);
-                                                                         }
-                                                                     } else {
-                                                                         found_source = LoadedImageSource::TextureBytes(buffer);
-                                                                     }
-                                                                     zip_found = true;
-                                                                 }
-                                                             }
-                                                         }
-                                                     }
-                                                 }
-                                             }
-                                         }
-                                     }
-                                     if zip_found { break; }
-                                     check_path = parent.to_path_buf();
-                                }
-                            }
-                            
-                            sender_clone.input(ImageViewMsg::ImageLoaded { index, source: found_source, path: path.clone(), generation: current_gen });
-                       }
-                   });
+                   self.manual_zoom = false;
+                   self.open_at = Some(if std::mem::take(&mut self.open_at_end) { PageCorner::End } else { PageCorner::Start });
+                   self.rescale_from = None;
+                   self.full_resolution = false;
+                   self.anim_paused = !self.auto_play;
+                   self.load_pages(paths);
                }
               ImageViewMsg::ImageLoaded { index, source, path, generation } => {
+                  if self.continuous_mode && self.strip.first_generation > 0 && generation >= self.strip.first_generation {
+                      self.strip_page_loaded(path, source, &_sender);
+                      return;
+                  }
                   if generation != self.generation {
                       return;
                   }
                   
                   let is_even = generation % 2 == 0;
+                  let transform = self.transform_for(&path);
 
                   // Helper to push to correct vector
                   let textures = if is_even {
@@ -754,46 +1052,50 @@ impl SimpleComponent for ImageViewModel {
                       LoadedImageSource::TextureBytes(bytes) => {
                           let glib_bytes = gtk4::glib::Bytes::from(&bytes);
                           if let Ok(texture) = gtk4::gdk::Texture::from_bytes(&glib_bytes) {
-                              textures.push(texture.upcast());
+                              textures.push(orient(texture.clone(), transform));
+                              if self.texture_cache.is_enabled() {
+                                  self.texture_cache.insert(path.clone(), texture);
+                              }
                           }
                       }
-                      LoadedImageSource::AnimFrames(frames) if !frames.is_empty() => {
-                          // Convert all frames to GDK textures on the main thread
-                          let gdk_frames: Vec<(gtk4::gdk::Texture, u32)> = frames.iter().filter_map(|f| {
-                              let bytes = gtk4::glib::Bytes::from(&f.rgba);
-                              let texture = gtk4::gdk::MemoryTexture::new(
-                                  f.width as i32,
-                                  f.height as i32,
-                                  gtk4::gdk::MemoryFormat::R8g8b8a8,
-                                  &bytes,
-                                  (f.width * 4) as usize,
-                              );
-                              Some((texture.upcast::<gtk4::gdk::Texture>(), f.delay_ms))
-                          }).collect();
-
-                          if gdk_frames.is_empty() { return; }
-
+                      LoadedImageSource::Decoded(image) => {
+                          let texture = decoded_texture(image);
+                          textures.push(orient(texture.clone(), transform));
+                          if self.texture_cache.is_enabled() {
+                              self.texture_cache.insert(path.clone(), texture);
+                          }
+                      }
+                      LoadedImageSource::Animation { stream, first, plays } => {
                           let slot_index = textures.len();
-                          textures.push(gdk_frames[0].0.clone().upcast::<gtk4::gdk::Paintable>());
-
-                          if gdk_frames.len() > 1 {
-                              let delay_ms = gdk_frames[0].1;
-                              self.anim_data.insert((slot_index, generation), gdk_frames);
-                              let sender_clone = _sender.clone();
-                              gtk4::glib::timeout_add_local(
-                                  std::time::Duration::from_millis(delay_ms as u64),
-                                  move || {
-                                      sender_clone.input(ImageViewMsg::AdvanceAnimFrame {
-                                          slot_index,
-                                          generation,
-                                          next_frame_index: 1,
-                                      });
-                                      gtk4::glib::ControlFlow::Break
-                                  },
-                              );
+                          let delay_ms = first.delay_ms;
+                          textures.push(orient(frame_texture(first), transform));
+                          let animation = Animation {
+                              stream,
+                              plays,
+                              current: 0,
+                              delay_ms,
+                              frame_count: None,
+                              play: 0,
+                              finished: false,
+                              stepping: false,
+                              seeking: false,
+                              timer_token: 0,
+                              transform,
+                          };
+                          self.anim_data.insert((slot_index, generation), animation);
+                          if !self.anim_paused {
+                              self.schedule_anim_frame((slot_index, generation), self.frame_delay(delay_ms), &_sender);
                           }
                       }
-                      LoadedImageSource::AnimFrames(_) | LoadedImageSource::Error => {
+                      LoadedImageSource::Locked(err) => {
+                          let key = match err {
+                              PasswordError::Required(_) => "This archive is password protected.",
+                              PasswordError::Incorrect(_) => "Incorrect password for this archive.",
+                          };
+                          self.error_message = Some(crate::i18n::localize(key, self.language));
+                          let _ = _sender.output(ImageViewOutput::PasswordRequired(err));
+                      }
+                      LoadedImageSource::Error => {
                           if path.exists() {
                               if let Ok(texture) = gtk4::gdk::Texture::from_file(&gtk4::gio::File::for_path(&path)) {
                                   textures.push(texture.upcast());
@@ -806,46 +1108,90 @@ impl SimpleComponent for ImageViewModel {
                   // before we show them. This prevents flicker in spread view (1 image -> 2 images).
                   if index == self.current_paths.len().saturating_sub(1) {
                       self.visible_generation = generation;
+                      if let Some(old_width) = self.rescale_from.take() {
+                          let textures = if is_even { &self.textures_even } else { &self.textures_odd };
+                          let new_width = textures.first().map_or(0.0, |t| t.intrinsic_width() as f64);
+                          if new_width > 0.0 {
+                              self.zoom *= old_width / new_width;
+                          }
+                      }
+                      self.pages_shown();
                   }
               }
               ImageViewMsg::ViewportResized(w, h) => {
                   self.viewport_size = (w, h);
-              }
-              ImageViewMsg::ZoomIn => {
-                  if self.is_fit_to_window {
-                      let new_zoom = self.calculate_current_fit_zoom();
-                      if new_zoom > 0.0 {
-                          self.zoom = new_zoom;
+                  if self.continuous_mode {
+                      self.layout_strip();
+                      self.update_strip(&_sender);
+                  } else {
+                      if !self.manual_zoom {
+                          self.apply_fit_mode();
                       }
-                      self.is_fit_to_window = false;
+                      self.refresh_resolution();
                   }
-                  self.zoom *= 1.05;
               }
-              ImageViewMsg::ZoomOut => {
-                  if self.is_fit_to_window {
-                      // Already at fit-to-window (minimum zoom), so ignore zoom out.
+              ImageViewMsg::Prefetch(paths) => {
+                  if !self.texture_cache.is_enabled() {
                       return;
                   }
-
-                  // Calculate the limit (fit zoom) based on current viewport
-                  let fit_zoom = self.calculate_current_fit_zoom();
-                  let mut new_zoom = self.zoom / 1.05;
-
-                  // Enforce lower bound (cannot zoom out smaller than fit-to-window)
-                  if new_zoom < fit_zoom {
-                      new_zoom = fit_zoom;
-                      // Optionally, we could set is_fit_to_window = true here if we snapped exactly,
-                      // but keeping it simple as requested (just restricting size).
+                  let wanted: Vec<PathBuf> = paths.into_iter().filter(|p| !self.texture_cache.contains(p)).collect();
+                  let _ = self.prefetch_sender.send(PrefetchRequest { paths: wanted, decode_box: self.decode_box() });
+              }
+              ImageViewMsg::PrefetchLoaded { path, image } => {
+                  self.texture_cache.insert(path, decoded_texture(image));
+              }
+              ImageViewMsg::UpdateCacheBudget(bytes) => {
+                  self.texture_cache.set_budget(bytes);
+              }
+              ImageViewMsg::UpdateAutoPlay(auto_play) => {
+                  self.auto_play = auto_play;
+              }
+              ImageViewMsg::UpdateDownscale(enabled) => {
+                  // Cached textures were decoded at the old size
+                  if self.downscale_large_images != enabled {
+                      self.downscale_large_images = enabled;
+                      self.texture_cache.retain(|_| false);
+                      self.reload(&_sender);
                   }
-
-                  if new_zoom < 0.01 { new_zoom = 0.01; }
-                  self.zoom = new_zoom;
               }
-              ImageViewMsg::ResetZoom => {
-                  self.is_fit_to_window = false;
-                  self.zoom = 1.0;
+              ImageViewMsg::UpdateColorManagement(enabled) => {
+                  // Cached textures were decoded with the old setting
+                  if crate::codecs::set_color_management(enabled) {
+                      self.texture_cache.retain(|_| false);
+                      self.reload(&_sender);
+                  }
+              }
+              ImageViewMsg::ZoomIn => self.zoom_step_by(true, None),
+              ImageViewMsg::ZoomOut => self.zoom_step_by(false, None),
+              ImageViewMsg::ResetZoom => self.zoom_to(1.0, None),
+              ImageViewMsg::UpdateZoomLimits { step, min, max } => {
+                  self.zoom_step = step;
+                  self.min_zoom = min;
+                  self.max_zoom = max;
+                  if !self.is_fit_to_window {
+                      self.zoom = self.zoom.clamp(min, max);
+                  }
+              }
+              ImageViewMsg::UpdateTransforms { folder, pages } => {
+                  self.folder_transform = folder;
+                  self.page_transforms = pages;
+              }
+              ImageViewMsg::UpdateRotateWholeFolder(enabled) => self.rotate_whole_folder = enabled,
+              ImageViewMsg::PinchBegin(center) => {
+                  self.pinch = Some((self.current_zoom(), center));
               }
-              ImageViewMsg::UpdateSettings { spread_mode, right_to_left, dir_sort, image_sort, input_map, language } => {
+              ImageViewMsg::PinchZoom(scale) => {
+                  if let Some((start_zoom, center)) = self.pinch {
+                      self.zoom_to(start_zoom * scale, center);
+                  }
+              }
+              ImageViewMsg::PinchEnd => {
+                  // Sharper pages are only worth loading once the fingers settle
+                  if self.pinch.take().is_some() {
+                      self.refresh_resolution();
+                  }
+              }
+              ImageViewMsg::UpdateSettings { spread_mode, continuous_mode, fit_mode, right_to_left, dir_sort, image_sort, input_map, language } => {
                   // Update dropdowns with flag set to suppress the synchronous notify::selected
                   // callback that GTK4 fires when set_selected changes the value.
                   if dir_sort != self.dir_sort {
@@ -856,6 +1202,8 @@ impl SimpleComponent for ImageViewModel {
                           SortType::DateDesc => 3,
                           SortType::SizeAsc => 4,
                           SortType::SizeDesc => 5,
+                          SortType::SeriesAsc => 6,
+                          SortType::SeriesDesc => 7,
                       };
                       if let Some(ref dd) = self.dir_sort_dropdown {
                           self.programmatic_dir_change.set(true);
@@ -871,6 +1219,9 @@ impl SimpleComponent for ImageViewModel {
                           SortType::DateDesc => 3,
                           SortType::SizeAsc => 4,
                           SortType::SizeDesc => 5,
+                          // Images have no series; they sort by name
+                          SortType::SeriesAsc => 0,
+                          SortType::SeriesDesc => 1,
                       };
                       if let Some(ref dd) = self.img_sort_dropdown {
                           self.programmatic_img_change.set(true);
@@ -878,7 +1229,17 @@ impl SimpleComponent for ImageViewModel {
                           self.programmatic_img_change.set(false);
                       }
                   }
+                  if fit_mode != self.fit_mode {
+                      if let Some(ref dd) = self.fit_mode_dropdown {
+                          let idx = FitMode::variants().iter().position(|m| *m == fit_mode).unwrap_or(0);
+                          self.programmatic_fit_change.set(true);
+                          dd.set_selected(idx as u32);
+                          self.programmatic_fit_change.set(false);
+                      }
+                      self.set_fit_mode(fit_mode);
+                  }
                   self.spread_mode = spread_mode;
+                  self.set_continuous_mode(continuous_mode);
                   self.right_to_left = right_to_left;
                   self.dir_sort = dir_sort;
                   self.image_sort = image_sort;
@@ -897,10 +1258,43 @@ impl SimpleComponent for ImageViewModel {
                       let _ = _sender.output(ImageViewOutput::ImageSortChanged(sort));
                   }
               }
+              ImageViewMsg::ChangeFitMode(mode) => {
+                  // Picking the mode again drops a manual zoom
+                  self.set_fit_mode(mode);
+                  let _ = _sender.output(ImageViewOutput::FitModeChanged(mode));
+              }
+              ImageViewMsg::UpdateComicInfo(info) => {
+                  self.comic_info = info;
+              }
+              ImageViewMsg::UpdateNameEncoding(encoding) => {
+                  if let (Some(encoding), Some(dd)) = (encoding, &self.name_encoding_dropdown) {
+                      let idx = NameEncoding::variants().iter().position(|e| *e == encoding).unwrap_or(0);
+                      self.programmatic_encoding_change.set(true);
+                      dd.set_selected(idx as u32);
+                      self.programmatic_encoding_change.set(false);
+                  }
+                  self.name_encoding = encoding;
+              }
+              ImageViewMsg::ChangeNameEncoding(encoding) => {
+                  if self.name_encoding.is_some_and(|e| e != encoding) {
+                      self.name_encoding = Some(encoding);
+                      let _ = _sender.output(ImageViewOutput::NameEncodingChanged(encoding));
+                  }
+              }
               ImageViewMsg::ToggleSpread => {
                    self.spread_mode = !self.spread_mode;
                    let _ = _sender.output(ImageViewOutput::SpreadModeChanged(self.spread_mode));
               }
+              ImageViewMsg::ToggleContinuous => {
+                   self.set_continuous_mode(!self.continuous_mode);
+                   let _ = _sender.output(ImageViewOutput::ContinuousModeChanged(self.continuous_mode));
+              }
+              ImageViewMsg::ShowStrip { pages, current } => {
+                   self.show_strip(pages, current, &_sender);
+              }
+              ImageViewMsg::StripScrolled => {
+                   self.update_strip(&_sender);
+              }
               ImageViewMsg::ToggleDirection => {
                    self.right_to_left = !self.right_to_left;
                    let _ = _sender.output(ImageViewOutput::RTLChanged(self.right_to_left));
@@ -908,56 +1302,111 @@ impl SimpleComponent for ImageViewModel {
                ImageViewMsg::UpdateFullscreen(val) => {
                    self.is_fullscreen = val;
                }
-               ImageViewMsg::AdvanceAnimFrame { slot_index, generation, next_frame_index } => {
+               ImageViewMsg::AdvanceAnimFrame { slot_index, generation, token } => {
                    if generation != self.generation && generation != self.visible_generation {
                        return;
                    }
-
-                   let (next_texture, delay_ms, total_frames) = {
-                       let Some(anim) = self.anim_data.get(&(slot_index, generation)) else { return; };
-                       let Some((texture, delay)) = anim.get(next_frame_index) else { return; };
-                       (texture.clone(), *delay, anim.len())
-                   };
-
-                   let textures = if generation % 2 == 0 { &mut self.textures_even } else { &mut self.textures_odd };
-                   if let Some(slot) = textures.get_mut(slot_index) {
-                       *slot = next_texture.upcast::<gtk4::gdk::Paintable>();
+                   let key = (slot_index, generation);
+                   let Some(anim) = self.anim_data.get(&key) else { return; };
+                   if token != anim.timer_token || (self.anim_paused && !anim.stepping) {
+                       return;
                    }
-
-                   let next_next = (next_frame_index + 1) % total_frames;
-                   let sender_clone = _sender.clone();
-                   gtk4::glib::timeout_add_local(
-                       std::time::Duration::from_millis(delay_ms as u64),
-                       move || {
-                           sender_clone.input(ImageViewMsg::AdvanceAnimFrame {
-                               slot_index,
-                               generation,
-                               next_frame_index: next_next,
-                           });
-                           gtk4::glib::ControlFlow::Break
-                       },
-                   );
+                   self.advance_animation(key, &_sender);
                }
                ImageViewMsg::TriggerAction(action) => {
                    match action {
-                       Action::ZoomIn => { 
-                           if self.is_fit_to_window {
-                               self.is_fit_to_window = false;
-                               self.zoom = self.calculate_current_fit_zoom();
+                       Action::ZoomIn => self.zoom_step_by(true, None),
+                       Action::ZoomOut => self.zoom_step_by(false, None),
+                       Action::ResetZoom => self.zoom_to(1.0, None),
+                       Action::SmartNextPage | Action::SmartPrevPage => {
+                           let forward = action == Action::SmartNextPage;
+                           if !self.smart_scroll(forward) {
+                               // Read through: turn the page, and continue reading from the matching corner
+                               self.open_at_end = !forward && !self.continuous_mode;
+                               let turn = if forward { Action::NextPage } else { Action::PrevPage };
+                               let _ = _sender.output(ImageViewOutput::TriggerAction(turn));
+                           }
+                       },
+                       Action::RotateClockwise | Action::RotateCounterClockwise | Action::Rotate180
+                       | Action::FlipHorizontal | Action::FlipVertical => {
+                           let change = |transform: PageTransform| match action {
+                               Action::RotateClockwise => transform.rotated(1),
+                               Action::RotateCounterClockwise => transform.rotated(3),
+                               Action::Rotate180 => transform.rotated(2),
+                               Action::FlipHorizontal => transform.flipped_horizontally(),
+                               _ => transform.flipped_vertically(),
+                           };
+                           self.change_transforms(change, &_sender);
+                       },
+                       Action::FitPage | Action::FitWidth | Action::FitHeight | Action::FitOriginal | Action::FitWidthCapped => {
+                           let mode = match action {
+                               Action::FitWidth => FitMode::Width,
+                               Action::FitHeight => FitMode::Height,
+                               Action::FitOriginal => FitMode::Original,
+                               Action::FitWidthCapped => FitMode::WidthCapped,
+                               _ => FitMode::Page,
+                           };
+                           let idx = FitMode::variants().iter().position(|m| *m == mode).unwrap_or(0);
+                           if let Some(ref dd) = self.fit_mode_dropdown {
+                               self.programmatic_fit_change.set(true);
+                               dd.set_selected(idx as u32);
+                               self.programmatic_fit_change.set(false);
                            }
-                           self.zoom *= 1.05;
+                           _sender.input(ImageViewMsg::ChangeFitMode(mode));
                        },
-                       Action::ZoomOut => { 
-                           if self.is_fit_to_window {
-                               self.is_fit_to_window = false;
-                               self.zoom = self.calculate_current_fit_zoom();
+                       Action::ToggleAnimation => {
+                           self.anim_paused = !self.anim_paused;
+                           let keys: Vec<(usize, u32)> = self.anim_data.keys().copied().collect();
+                           for key in keys {
+                               let resume = !self.anim_paused;
+                               let speed = self.anim_speed;
+                               let Some(anim) = self.anim_data.get_mut(&key) else { continue; };
+                               anim.timer_token += 1;
+                               if !resume {
+                                   continue;
+                               }
+                               // A finished animation has already been rewound; play it again
+                               let delay = if anim.finished {
+                                   anim.finished = false;
+                                   anim.play = 0;
+                                   std::time::Duration::ZERO
+                               } else {
+                                   std::time::Duration::from_millis((anim.delay_ms as f64 / speed) as u64)
+                               };
+                               self.schedule_anim_frame(key, delay, &_sender);
                            }
-                           self.zoom /= 1.05;
-                           if self.zoom < 0.01 { self.zoom = 0.01; }
                        },
-                       Action::ResetZoom => { 
-                           self.is_fit_to_window = false;
-                           self.zoom = 1.0;
+                       Action::NextFrame | Action::PrevFrame => {
+                           // Stepping pauses playback so the frame stays put
+                           self.anim_paused = true;
+                           let keys: Vec<(usize, u32)> = self.anim_data.keys().copied().collect();
+                           for key in keys {
+                               let Some(anim) = self.anim_data.get_mut(&key) else { continue; };
+                               anim.timer_token += 1;
+                               if action == Action::PrevFrame && !anim.finished {
+                                   let target = match (anim.current, anim.frame_count) {
+                                       (0, Some(count)) => count - 1,
+                                       // Before the first loop the last frame is not known yet
+                                       (0, None) => continue,
+                                       (current, _) => current - 1,
+                                   };
+                                   anim.stream.seek(target);
+                                   anim.seeking = true;
+                               } else if action == Action::PrevFrame {
+                                   // Finished on the last frame, with the stream rewound to the first
+                                   let target = anim.current.saturating_sub(1);
+                                   anim.stream.seek(target);
+                               }
+                               anim.finished = false;
+                               anim.stepping = true;
+                               self.schedule_anim_frame(key, std::time::Duration::ZERO, &_sender);
+                           }
+                       },
+                       Action::AnimationFaster => {
+                           self.anim_speed = (self.anim_speed * 2.0).min(MAX_ANIM_SPEED);
+                       },
+                       Action::AnimationSlower => {
+                           self.anim_speed = (self.anim_speed / 2.0).max(MIN_ANIM_SPEED);
                        },
                         _ => {
                             let _ = _sender.output(ImageViewOutput::TriggerAction(action));
@@ -969,10 +1418,14 @@ impl SimpleComponent for ImageViewModel {
                         _sender.input(ImageViewMsg::TriggerAction(action));
                     }
                }
-               ImageViewMsg::ScrollInput { dy, modifiers } => {
+               ImageViewMsg::ScrollInput { dy, modifiers, position } => {
                     let direction = if dy < 0.0 { ScrollDirection::Up } else { ScrollDirection::Down };
-                    if let Some(action) = self.input_map.get_action_for_scroll(direction, gtk4::gdk::ModifierType::from_bits_truncate(modifiers)) {
-                         _sender.input(ImageViewMsg::TriggerAction(action));
+                    match self.input_map.get_action_for_scroll(direction, gtk4::gdk::ModifierType::from_bits_truncate(modifiers)) {
+                         // Wheel zoom keeps what is under the pointer in place
+                         Some(Action::ZoomIn) => self.zoom_step_by(true, position),
+                         Some(Action::ZoomOut) => self.zoom_step_by(false, position),
+                         Some(action) => _sender.input(ImageViewMsg::TriggerAction(action)),
+                         None => {}
                     }
                }
          }
@@ -982,12 +1435,598 @@ impl SimpleComponent for ImageViewModel {
     }
 
 impl ImageViewModel {
+    fn load_pages(&mut self, paths: Vec<PathBuf>) {
+        self.current_paths = paths.clone();
+        self.error_message = None;
+        
+        // Increment generation for new request
+        self.generation += 1;
+        let current_gen = self.generation;
+        
+        // Clear the buffer we are about to load into (the one NOT visible ideally, but here determined by generation)
+        // If visible_generation == generation - 1, then visible_generation % 2 != generation % 2
+        // So we are safe to clear generation % 2
+        let is_even = current_gen % 2 == 0;
+        if is_even {
+            self.textures_even.clear();
+        } else {
+            self.textures_odd.clear();
+        }
+        // Remove anim_data for generations no longer displayed
+        let vg = self.visible_generation;
+        self.anim_data.retain(|(_, g), _| *g == vg);
+        
+        if paths.is_empty() {
+            self.visible_generation = current_gen;
+            return;
+        }
+
+        // Everything already decoded (prefetched or seen recently): show it right away.
+        // Partial hits still go through the loader to keep the page order intact.
+        if paths.iter().all(|p| self.texture_cache.contains(p)) {
+            let cached: Vec<gtk4::gdk::Paintable> = paths.iter()
+                .filter_map(|p| self.texture_cache.get(p).map(|t| orient(t, self.transform_for(p))))
+                .collect();
+            if is_even {
+                self.textures_even = cached;
+            } else {
+                self.textures_odd = cached;
+            }
+            self.visible_generation = current_gen;
+            self.pages_shown();
+            return;
+        }
+
+        self.latest_generation.store(current_gen, std::sync::atomic::Ordering::Relaxed);
+        let job = LoadJob { paths, generation: current_gen, decode_box: self.decode_box() };
+        if self.load_sender.send(job).is_err() {
+            eprintln!("Page loader threads have stopped");
+        }
+    }
+
+    /// Load the shown pages again, e.g. after a decoding setting changed.
+    fn reload(&mut self, sender: &ComponentSender<Self>) {
+        if self.continuous_mode {
+            let pages = std::mem::take(&mut self.strip.pages);
+            let current = self.strip.current;
+            if let Some(path) = pages.get(current).cloned() {
+                self.show_strip(pages, path, sender);
+            }
+        } else if !self.current_paths.is_empty() {
+            self.load_pages(self.current_paths.clone());
+        }
+    }
+
+    fn set_continuous_mode(&mut self, enabled: bool) {
+        if self.continuous_mode == enabled {
+            return;
+        }
+        self.continuous_mode = enabled;
+        if enabled {
+            // The paged view is hidden until the mode is left; let go of its pages
+            self.generation += 1;
+            self.visible_generation = self.generation;
+            self.current_paths.clear();
+            self.textures_even.clear();
+            self.textures_odd.clear();
+            self.anim_data.clear();
+        } else {
+            self.clear_strip();
+        }
+    }
+
+    /// Show `pages` in the continuous view scrolled to `current`. When the pages are
+    /// already there, only scroll.
+    fn show_strip(&mut self, pages: Vec<PathBuf>, current: PathBuf, sender: &ComponentSender<Self>) {
+        let index = pages.iter().position(|p| *p == current).unwrap_or(0);
+        // A failed unlock is retried with the new password
+        if pages != self.strip.pages || self.error_message.is_some() {
+            self.build_strip(pages);
+        } else if index == self.strip.current {
+            return;
+        }
+        self.strip.current = index;
+        self.strip.scroll_target = Some(index);
+        self.update_strip(sender);
+    }
+
+    fn build_strip(&mut self, pages: Vec<PathBuf>) {
+        self.clear_strip();
+        self.error_message = None;
+        let Some(strip_box) = &self.strip_box else { return; };
+        for path in &pages {
+            let picture = gtk4::Picture::new();
+            picture.set_can_shrink(true);
+            strip_box.append(&picture);
+            self.strip.pictures.push(picture);
+            self.strip.sizes.push(self.page_size(path));
+        }
+        self.strip.pages = pages;
+        // Loads from before belong to other pages
+        self.generation += 1;
+        self.strip.first_generation = self.generation;
+        self.layout_strip();
+    }
+
+    fn clear_strip(&mut self) {
+        if let Some(strip_box) = &self.strip_box {
+            for picture in &self.strip.pictures {
+                strip_box.remove(picture);
+            }
+        }
+        self.strip = Strip::default();
+    }
+
+    /// Height of a page in the continuous view. Pages of unknown size take a screenful,
+    /// so that their neighbours do not all count as visible at once.
+    fn strip_page_height(&self, index: usize) -> f64 {
+        let (view_w, view_h) = self.viewport_size;
+        match self.strip.sizes.get(index).copied().flatten() {
+            Some((width, height)) if width > 0 => (view_w * height as f64 / width as f64).round(),
+            _ => view_h.round(),
+        }
+    }
+
+    /// Top of every page in the continuous view, followed by the total height.
+    fn strip_offsets(&self) -> Vec<f64> {
+        let mut offsets = Vec::with_capacity(self.strip.pages.len() + 1);
+        let mut y = 0.0;
+        offsets.push(y);
+        for index in 0..self.strip.pages.len() {
+            y += self.strip_page_height(index);
+            offsets.push(y);
+        }
+        offsets
+    }
+
+    fn layout_strip(&self) {
+        for (index, picture) in self.strip.pictures.iter().enumerate() {
+            picture.set_height_request(self.strip_page_height(index) as i32);
+        }
+    }
+
+    /// Record the size of a page. Pages above the view changing height would push the
+    /// visible ones around, so the scroll position moves along with them.
+    fn set_strip_page_size(&mut self, index: usize, size: (u32, u32)) {
+        if self.strip.sizes[index] == Some(size) {
+            return;
+        }
+        let old_height = self.strip_page_height(index);
+        self.strip.sizes[index] = Some(size);
+        let new_height = self.strip_page_height(index);
+        self.strip.pictures[index].set_height_request(new_height as i32);
+
+        let Some(sw) = &self.strip_window else { return; };
+        let adjustment = sw.vadjustment();
+        if self.strip.scroll_target.is_none() && index < self.strip.current {
+            adjustment.set_value(adjustment.value() + new_height - old_height);
+        }
+    }
+
+    /// Bring a pending page into view, load the pages around the visible part, drop the
+    /// far ones, and report the page now under the top of the view.
+    fn update_strip(&mut self, sender: &ComponentSender<Self>) {
+        let Some(sw) = self.strip_window.clone() else { return; };
+        if self.strip.pages.is_empty() || self.viewport_size.1 <= 0.0 {
+            return;
+        }
+        let adjustment = sw.vadjustment();
+
+        // Sizes the dimension cache learned since the strip was built
+        for index in 0..self.strip.pages.len() {
+            if self.strip.sizes[index].is_none() {
+                if let Some(size) = self.page_size(&self.strip.pages[index]) {
+                    self.set_strip_page_size(index, size);
+                }
+            }
+        }
+
+        let offsets = self.strip_offsets();
+        let last_page = self.strip.pages.len() - 1;
+        if let Some(target) = self.strip.scroll_target {
+            // The scrolled window only learns the new heights on its next layout
+            if adjustment.upper() + 1.0 < offsets[last_page + 1] {
+                return;
+            }
+            self.strip.scroll_target = None;
+            adjustment.set_value(offsets[target]);
+        }
+
+        let top = adjustment.value();
+        let bottom = top + adjustment.page_size();
+        let page_at = |y: f64| offsets.partition_point(|&offset| offset <= y).saturating_sub(1).min(last_page);
+        let first = page_at(top);
+        let last = page_at(bottom - 1.0).max(first);
+
+        let current = page_at(top + adjustment.page_size() / 4.0);
+        if current != self.strip.current {
+            self.strip.current = current;
+            let _ = sender.output(ImageViewOutput::PageInView(self.strip.pages[current].clone()));
+        }
+
+        let keep = first.saturating_sub(STRIP_KEEP_MARGIN)..=(last + STRIP_KEEP_MARGIN).min(last_page);
+        let far: Vec<usize> = self.strip.shown.iter().copied().filter(|i| !keep.contains(i)).collect();
+        for index in far {
+            self.strip.pictures[index].set_paintable(None::<&gtk4::gdk::Paintable>);
+            self.strip.shown.remove(&index);
+        }
+        self.strip.pending.retain(|i| keep.contains(i));
+
+        // Visible pages first, then the ones below, then the ones above
+        let load_end = (last + STRIP_LOAD_MARGIN).min(last_page);
+        let load_start = first.saturating_sub(STRIP_LOAD_MARGIN);
+        let mut wanted = Vec::new();
+        for index in (first..=load_end).chain((load_start..first).rev()) {
+            if self.strip.shown.contains(&index) {
+                continue;
+            }
+            match self.texture_cache.get(&self.strip.pages[index]) {
+                Some(texture) => self.show_strip_page(index, texture.upcast()),
+                None => wanted.push(index),
+            }
+        }
+
+        // The running job already covers these
+        if wanted.iter().all(|i| self.strip.pending.contains(i)) {
+            return;
+        }
+        self.generation += 1;
+        self.latest_generation.store(self.generation, std::sync::atomic::Ordering::Relaxed);
+        self.strip.pending = wanted.iter().copied().collect();
+        let paths = wanted.iter().map(|&i| self.strip.pages[i].clone()).collect();
+        // Pages are as wide as the view; their height is whatever the page needs
+        let decode_box = self.decode_box().map(|(width, _)| (width, u32::MAX));
+        let job = LoadJob { paths, generation: self.generation, decode_box };
+        if self.load_sender.send(job).is_err() {
+            eprintln!("Page loader threads have stopped");
+        }
+    }
+
+    fn show_strip_page(&mut self, index: usize, paintable: gtk4::gdk::Paintable) {
+        let size = (paintable.intrinsic_width().max(0) as u32, paintable.intrinsic_height().max(0) as u32);
+        self.set_strip_page_size(index, size);
+        self.strip.pictures[index].set_paintable(Some(&paintable));
+        self.strip.shown.insert(index);
+    }
+
+    fn strip_page_loaded(&mut self, path: PathBuf, source: LoadedImageSource, sender: &ComponentSender<Self>) {
+        let Some(index) = self.strip.pages.iter().position(|p| *p == path) else { return; };
+        // Scrolled away before it arrived
+        if !self.strip.pending.remove(&index) {
+            return;
+        }
+        let transform = self.transform_for(&path);
+        let texture = match source {
+            LoadedImageSource::TextureBytes(bytes) => {
+                let Ok(texture) = gtk4::gdk::Texture::from_bytes(&gtk4::glib::Bytes::from(&bytes)) else { return; };
+                texture
+            }
+            LoadedImageSource::Decoded(image) => decoded_texture(image),
+            // Animations stay on their first frame in the strip
+            LoadedImageSource::Animation { first, .. } => {
+                self.show_strip_page(index, orient(frame_texture(first), transform));
+                return;
+            }
+            LoadedImageSource::Locked(err) => {
+                // Asked once for the whole strip
+                if self.error_message.is_none() {
+                    let key = match err {
+                        PasswordError::Required(_) => "This archive is password protected.",
+                        PasswordError::Incorrect(_) => "Incorrect password for this archive.",
+                    };
+                    self.error_message = Some(crate::i18n::localize(key, self.language));
+                    let _ = sender.output(ImageViewOutput::PasswordRequired(err));
+                }
+                return;
+            }
+            LoadedImageSource::Error => return,
+        };
+        if self.texture_cache.is_enabled() {
+            self.texture_cache.insert(path, texture.clone());
+        }
+        self.show_strip_page(index, orient(texture, transform));
+    }
+
+    /// Box pages are downscaled to fit while decoding, when enabled. Twice the viewport,
+    /// to stay sharp on HiDPI screens and for moderate zoom.
+    fn decode_box(&self) -> Option<(u32, u32)> {
+        let (view_w, view_h) = self.viewport_size;
+        if !self.downscale_large_images || self.full_resolution || view_w <= 0.0 || view_h <= 0.0 {
+            return None;
+        }
+        Some(((view_w * DOWNSCALE_HEADROOM) as u32, (view_h * DOWNSCALE_HEADROOM) as u32))
+    }
+
+    /// Reload the shown pages when the viewport or zoom needs noticeably more pixels than
+    /// they were rendered or downscaled to. Zooming out keeps the sharper pages.
+    fn refresh_resolution(&mut self) {
+        let (view_w, view_h) = self.viewport_size;
+        if view_w <= 0.0 || view_h <= 0.0 { return; }
+
+        let fit_zoom = self.calculate_current_fit_zoom();
+        let factor = if self.is_fit_to_window { 1.0 } else { (self.zoom / fit_zoom).clamp(1.0, MAX_PDF_ZOOM_FACTOR) };
+        let reload_pdf = self.grow_pdf_render_box(view_w * factor, view_h * factor);
+
+        // Downscaled pages shown larger than their decoded size go back to full resolution
+        let scale = if self.is_fit_to_window { fit_zoom } else { self.zoom };
+        let reload_downscaled = scale > 1.0 && !self.full_resolution && self.shows_downscaled_page();
+        if reload_downscaled {
+            self.full_resolution = true;
+            let paths = self.current_paths.clone();
+            self.texture_cache.retain(|p| !paths.iter().any(|shown| shown == p));
+        }
+
+        if reload_pdf || reload_downscaled {
+            self.rescale_from = self.visible_textures().first().map(|t| t.intrinsic_width() as f64);
+            self.load_pages(self.current_paths.clone());
+        }
+    }
+
+    /// Grow the box PDF pages are rendered into. True when pages on screen need re-rendering.
+    fn grow_pdf_render_box(&mut self, wanted_w: f64, wanted_h: f64) -> bool {
+        let (current_w, current_h) = crate::page_source::pdf_render_box();
+        if wanted_w < current_w as f64 * 1.25 && wanted_h < current_h as f64 * 1.25 {
+            return false;
+        }
+        crate::page_source::set_pdf_render_box((wanted_w as u32).max(current_w), (wanted_h as u32).max(current_h));
+        crate::dimension_cache::forget_pdf_pages();
+        self.texture_cache.retain(|p| !crate::page_source::is_pdf_page(p));
+        self.current_paths.iter().any(|p| crate::page_source::is_pdf_page(p))
+    }
+
+    /// Whether a page on screen was decoded smaller than the image in the file.
+    fn shows_downscaled_page(&self) -> bool {
+        if !self.downscale_large_images {
+            return false;
+        }
+        self.current_paths.iter()
+            .zip(self.visible_textures())
+            .filter(|(path, _)| !crate::page_source::is_pdf_page(path))
+            .any(|(path, texture)| crate::dimension_cache::get(path)
+                .map(|size| self.transform_for(path).oriented_size(size))
+                .is_some_and(|(width, _)| (texture.intrinsic_width() as u32) < width))
+    }
+
+    /// Show the next decoded frame of an animation and time the one after it.
+    fn advance_animation(&mut self, key: (usize, u32), sender: &ComponentSender<Self>) {
+        let paused = self.anim_paused;
+        let Some(anim) = self.anim_data.get_mut(&key) else { return; };
+        let frame = match anim.stream.poll() {
+            FramePoll::Ready(frame) => frame,
+            FramePoll::Pending => {
+                self.schedule_anim_frame(key, ANIM_POLL, sender);
+                return;
+            }
+            FramePoll::Ended => return,
+        };
+        if std::mem::take(&mut anim.seeking) {
+            // Answer to a seek, not the next frame in sequence
+        } else if frame.index == 0 && anim.current > 0 {
+            anim.frame_count = Some(anim.current + 1);
+            anim.play += 1;
+            if !anim.stepping && anim.plays.is_some_and(|plays| anim.play >= plays) {
+                // Finite animations stay on their last frame. Rewind the stream so that
+                // resuming or stepping starts from the first frame.
+                anim.finished = true;
+                anim.stream.seek(0);
+                anim.seeking = true;
+                return;
+            }
+        }
+        anim.stepping = false;
+        anim.current = frame.index;
+        anim.delay_ms = frame.frame.delay_ms;
+        let delay_ms = anim.delay_ms;
+        let transform = anim.transform;
+
+        let (slot_index, generation) = key;
+        let textures = if generation % 2 == 0 { &mut self.textures_even } else { &mut self.textures_odd };
+        if let Some(slot) = textures.get_mut(slot_index) {
+            *slot = orient(frame_texture(frame.frame), transform);
+        }
+        if !paused {
+            self.schedule_anim_frame(key, self.frame_delay(delay_ms), sender);
+        }
+    }
+
+    /// Time until the next frame at the current playback speed.
+    fn frame_delay(&self, delay_ms: u32) -> std::time::Duration {
+        std::time::Duration::from_millis((delay_ms as f64 / self.anim_speed) as u64)
+    }
+
+    fn schedule_anim_frame(&self, key: (usize, u32), delay: std::time::Duration, sender: &ComponentSender<Self>) {
+        let Some(anim) = self.anim_data.get(&key) else { return; };
+        let (slot_index, generation) = key;
+        let token = anim.timer_token;
+        let sender = sender.clone();
+        gtk4::glib::timeout_add_local(delay, move || {
+            sender.input(ImageViewMsg::AdvanceAnimFrame { slot_index, generation, token });
+            gtk4::glib::ControlFlow::Break
+        });
+    }
+
+    /// Frame counter shown while animations are paused, e.g. "Frame 3 / 20".
+    fn anim_status(&self) -> Option<String> {
+        if !self.anim_paused {
+            return None;
+        }
+        let vg = self.visible_generation;
+        let mut keys: Vec<&(usize, u32)> = self.anim_data.keys().filter(|(_, g)| *g == vg).collect();
+        keys.sort();
+        let anim = self.anim_data.get(keys.first()?)?;
+        let mut status = format!("{} {}", crate::i18n::localize("Frame", self.language), anim.current + 1);
+        if let Some(count) = anim.frame_count {
+            status.push_str(&format!(" / {}", count));
+        }
+        if self.anim_speed != 1.0 {
+            status.push_str(&format!("  ×{}", self.anim_speed));
+        }
+        Some(status)
+    }
+
+    fn visible_textures(&self) -> &Vec<gtk4::gdk::Paintable> {
+        if self.visible_generation % 2 == 0 { &self.textures_even } else { &self.textures_odd }
+    }
+
+    fn set_fit_mode(&mut self, mode: FitMode) {
+        self.fit_mode = mode;
+        self.manual_zoom = false;
+        self.apply_fit_mode();
+        self.refresh_resolution();
+    }
+
+    /// Size the visible pages as the fit mode asks. Fit page keeps the layout that follows
+    /// the window; the other modes set an explicit zoom and scroll.
+    fn apply_fit_mode(&mut self) {
+        match self.fit_mode {
+            FitMode::Page => self.is_fit_to_window = true,
+            mode => {
+                self.is_fit_to_window = false;
+                self.zoom = self.fit_zoom(mode);
+            }
+        }
+    }
+
+    /// New pages are on screen: fit them, and start at their top left.
+    fn pages_shown(&mut self) {
+        if !self.manual_zoom {
+            self.apply_fit_mode();
+        }
+        if let Some(corner) = self.open_at.take() {
+            self.scroll_to_corner(corner);
+        }
+    }
+
+    /// Rotation and mirroring `path` is shown with.
+    fn transform_for(&self, path: &std::path::Path) -> PageTransform {
+        self.page_transforms.get(path).copied().unwrap_or(self.folder_transform)
+    }
+
+    /// Known size of a page as shown, i.e. after rotation.
+    fn page_size(&self, path: &std::path::Path) -> Option<(u32, u32)> {
+        crate::dimension_cache::lookup(path).map(|size| self.transform_for(path).oriented_size(size))
+    }
+
+    /// Rotate or flip the shown pages, or the whole folder, and show the result.
+    fn change_transforms(&mut self, change: impl Fn(PageTransform) -> PageTransform, sender: &ComponentSender<Self>) {
+        if self.rotate_whole_folder {
+            // Pages turned on their own turn along with the folder
+            self.folder_transform = change(self.folder_transform);
+            for transform in self.page_transforms.values_mut() {
+                *transform = change(*transform);
+            }
+        } else {
+            let shown = if self.continuous_mode {
+                self.strip.pages.get(self.strip.current).cloned().into_iter().collect()
+            } else {
+                self.current_paths.clone()
+            };
+            for path in shown {
+                let transform = change(self.transform_for(&path));
+                self.page_transforms.insert(path, transform);
+            }
+        }
+        let folder = self.folder_transform;
+        self.page_transforms.retain(|_, transform| *transform != folder);
+        self.reload(sender);
+        let _ = sender.output(ImageViewOutput::TransformsChanged { folder, pages: self.page_transforms.clone() });
+    }
+
+    fn current_zoom(&self) -> f64 {
+        if self.is_fit_to_window { self.calculate_current_fit_zoom() } else { self.zoom }
+    }
+
+    /// Zoom in or out by one step around `anchor`.
+    fn zoom_step_by(&mut self, zoom_in: bool, anchor: Option<(f64, f64)>) {
+        let current = self.current_zoom();
+        if zoom_in {
+            self.zoom_to(current * self.zoom_step, anchor);
+            self.refresh_resolution();
+        } else {
+            self.zoom_to(current / self.zoom_step, anchor);
+        }
+    }
+
+    /// Zoom to `zoom` within the configured limits, keeping the content under `anchor`
+    /// (a point in the viewport, its centre when None) where it is on screen.
+    fn zoom_to(&mut self, zoom: f64, anchor: Option<(f64, f64)>) {
+        let old_zoom = self.current_zoom();
+        let new_zoom = zoom.clamp(self.min_zoom, self.max_zoom);
+        self.is_fit_to_window = false;
+        self.manual_zoom = true;
+        self.zoom = new_zoom;
+
+        if self.continuous_mode || old_zoom <= 0.0 { return; }
+        let Some((content_w, content_h)) = self.content_size() else { return; };
+        let Some(sw) = self.page_windows.get((self.visible_generation % 2) as usize) else { return; };
+        let (view_w, view_h) = self.viewport_size;
+        let (x, y) = anchor.unwrap_or((view_w / 2.0, view_h / 2.0));
+        anchor_zoom(&sw.hadjustment(), content_w, view_w, x, old_zoom, new_zoom);
+        anchor_zoom(&sw.vadjustment(), content_h, view_h, y, old_zoom, new_zoom);
+    }
+
+    /// Scroll the visible pages to a corner. The scrolled window has not measured the new
+    /// pages yet, so their extent is worked out from the zoom.
+    fn scroll_to_corner(&self, corner: PageCorner) {
+        let Some(sw) = self.page_windows.get((self.visible_generation % 2) as usize) else { return; };
+        let (view_w, view_h) = self.viewport_size;
+        let (content_w, content_h) = match self.content_size() {
+            Some((w, h)) if !self.is_fit_to_window => (w * self.zoom, h * self.zoom),
+            _ => (view_w, view_h),
+        };
+        let at_end = corner == PageCorner::End;
+        let (hadjustment, vadjustment) = (sw.hadjustment(), sw.vadjustment());
+        hadjustment.set_upper(content_w.max(view_w));
+        vadjustment.set_upper(content_h.max(view_h));
+        hadjustment.set_value(if at_end != self.right_to_left { (content_w - view_w).max(0.0) } else { 0.0 });
+        vadjustment.set_value(if at_end { (content_h - view_h).max(0.0) } else { 0.0 });
+    }
+
+    /// Scroll one screen on (or back) in reading order: across the row first, leftwards
+    /// for right-to-left, then a screen down. False once the pages have been read through.
+    fn smart_scroll(&self, forward: bool) -> bool {
+        if self.continuous_mode {
+            return self.strip_window.as_ref().is_some_and(|sw| scroll_step(&sw.vadjustment(), forward));
+        }
+        let Some(sw) = self.page_windows.get((self.visible_generation % 2) as usize) else { return false; };
+        let (hadjustment, vadjustment) = (sw.hadjustment(), sw.vadjustment());
+        if scroll_step(&hadjustment, forward != self.right_to_left) {
+            return true;
+        }
+        if !scroll_step(&vadjustment, forward) {
+            return false;
+        }
+        // A row is entered from its reading-order start going forward, from its end going back
+        let from_right = forward == self.right_to_left;
+        hadjustment.set_value(if from_right { hadjustment.upper() - hadjustment.page_size() } else { hadjustment.lower() });
+        true
+    }
+
     fn calculate_current_fit_zoom(&self) -> f64 {
+        self.fit_zoom(FitMode::Page)
+    }
+
+    fn fit_zoom(&self, mode: FitMode) -> f64 {
         let (view_w, view_h) = self.viewport_size;
         if view_w <= 0.0 || view_h <= 0.0 { return 1.0; }
+        let Some((total_w, max_h)) = self.content_size() else { return 1.0; };
+
+        let scale_w = view_w / total_w;
+        let scale_h = view_h / max_h;
+
+        match mode {
+            FitMode::Page => scale_w.min(scale_h),
+            FitMode::Width => scale_w,
+            FitMode::Height => scale_h,
+            FitMode::Original => 1.0,
+            FitMode::WidthCapped => scale_w.min(1.0),
+        }
+    }
 
-        let textures = if self.visible_generation % 2 == 0 { &self.textures_even } else { &self.textures_odd };
-        if textures.is_empty() { return 1.0; }
+    /// Width and height of the visible pages side by side at 100%.
+    fn content_size(&self) -> Option<(f64, f64)> {
+        let textures = self.visible_textures();
+        if textures.is_empty() { return None; }
 
         let mut total_w: f64 = 0.0;
         let mut max_h: f64 = 0.0;
@@ -1011,39 +2050,156 @@ impl ImageViewModel {
              }
         }
         
-        if total_w <= 0.0 || max_h <= 0.0 { return 1.0; }
+        if total_w <= 0.0 || max_h <= 0.0 { return None; }
+        Some((total_w, max_h))
+    }
+}
 
-        let scale_w = view_w / total_w;
-        let scale_h = view_h / max_h;
-        
-        scale_w.min(scale_h)
+/// Background reader for upcoming pages. Only the newest request matters: when the user
+/// keeps turning pages, stale read-ahead lists are dropped instead of queueing up.
+fn spawn_prefetch_worker(receiver: std::sync::mpsc::Receiver<PrefetchRequest>, sender: relm4::Sender<ImageViewMsg>) {
+    std::thread::spawn(move || {
+        while let Ok(mut request) = receiver.recv() {
+            let mut next = 0;
+            while next < request.paths.len() {
+                if let Some(newer) = receiver.try_iter().last() {
+                    request = newer;
+                    next = 0;
+                    continue;
+                }
+                let path = &request.paths[next];
+                next += 1;
+
+                let Ok(data) = crate::page_source::read_page(path) else { continue; };
+                // Animations keep their own frame pipeline
+                if crate::codecs::is_animated(path, &data) {
+                    continue;
+                }
+                if let Ok(image) = decode_page(path, &data, request.decode_box) {
+                    sender.emit(ImageViewMsg::PrefetchLoaded { path: path.clone(), image });
+                }
+            }
+        }
+    });
+}
+
+/// Start the threads that read and decode the pages to show, so that the main thread
+/// only turns finished pixel buffers into textures.
+fn spawn_load_workers(jobs: std::sync::mpsc::Receiver<LoadJob>, latest: std::sync::Arc<std::sync::atomic::AtomicU32>, sender: relm4::Sender<ImageViewMsg>) {
+    let jobs = std::sync::Arc::new(std::sync::Mutex::new(jobs));
+    for _ in 0..LOAD_WORKERS {
+        let jobs = jobs.clone();
+        let latest = latest.clone();
+        let sender = sender.clone();
+        std::thread::spawn(move || loop {
+            let job = match jobs.lock().unwrap_or_else(|e| e.into_inner()).recv() {
+                Ok(job) => job,
+                Err(_) => return,
+            };
+            for (index, path) in job.paths.iter().enumerate() {
+                // Pages the user has already turned past
+                if latest.load(std::sync::atomic::Ordering::Relaxed) != job.generation {
+                    break;
+                }
+                let source = load_page(path, job.decode_box);
+                sender.emit(ImageViewMsg::ImageLoaded { index, source, path: path.clone(), generation: job.generation });
+            }
+        });
     }
 }
 
-fn decode_anim_frames(data: &[u8], ext: &str) -> Vec<AnimFrame> {
-    use image::AnimationDecoder;
-    let cursor = std::io::Cursor::new(data);
-    let frames = match ext {
-        "gif" => image::codecs::gif::GifDecoder::new(cursor)
-            .ok()
-            .and_then(|d| d.into_frames().collect_frames().ok()),
-        "webp" => image::codecs::webp::WebPDecoder::new(cursor)
-            .ok()
-            .and_then(|d| d.into_frames().collect_frames().ok()),
-        _ => None,
+fn load_page(path: &std::path::Path, decode_box: Option<(u32, u32)>) -> LoadedImageSource {
+    // Plain files and archive entries resolve through the same page source
+    let data = match crate::page_source::read_page(path) {
+        Ok(data) => data,
+        Err(e) => return match e.downcast_ref::<PasswordError>() {
+            Some(err) => LoadedImageSource::Locked(err.clone()),
+            None => {
+                eprintln!("Failed to read {}: {}", path.display(), e);
+                LoadedImageSource::Error
+            }
+        },
     };
+    if crate::codecs::is_animated(path, &data) {
+        // Only the first frame is waited for; the rest decode while playing
+        let plays = crate::codecs::play_count(path, &data);
+        return match AnimationStream::open(path.to_path_buf(), data.clone()) {
+            Some((stream, first)) => LoadedImageSource::Animation { stream, first, plays },
+            None => LoadedImageSource::TextureBytes(data),
+        };
+    }
+    match decode_page(path, &data, decode_box) {
+        Ok(image) => LoadedImageSource::Decoded(image),
+        Err(e) => {
+            // GDK may still know the format
+            eprintln!("Failed to decode {}: {}", path.display(), e);
+            LoadedImageSource::TextureBytes(data)
+        }
+    }
+}
 
-    let frames = match frames {
-        Some(f) if !f.is_empty() => f,
-        _ => return vec![],
-    };
+fn decode_page(path: &std::path::Path, data: &[u8], decode_box: Option<(u32, u32)>) -> anyhow::Result<DecodedImage> {
+    let mut rgba = crate::codecs::decode(path, data)?;
+    if let Some(decode_box) = decode_box {
+        rgba = crate::codecs::fit_within(rgba, decode_box);
+    }
+    let (width, height) = rgba.dimensions();
+    Ok(DecodedImage { rgba: rgba.into_raw(), width, height })
+}
+
+/// Scroll one axis so that the point at `pointer` stays put while the content of length
+/// `content` (at 100%) goes from `old_zoom` to `new_zoom`. Content smaller than the
+/// viewport is centred instead of scrolled.
+fn anchor_zoom(adjustment: &gtk4::Adjustment, content: f64, view: f64, pointer: f64, old_zoom: f64, new_zoom: f64) {
+    let old_extent = content * old_zoom;
+    let new_extent = content * new_zoom;
+    if new_extent <= view {
+        return;
+    }
+    let old_start = if old_extent < view { (view - old_extent) / 2.0 } else { -adjustment.value() };
+    let fraction = ((pointer - old_start) / old_extent).clamp(0.0, 1.0);
+    // The scrolled window only measures the new size on its next layout
+    adjustment.set_upper(new_extent);
+    adjustment.set_value((fraction * new_extent - pointer).clamp(0.0, new_extent - view));
+}
+
+/// Move `adjustment` one smart scroll step towards its end (or start). False when already there.
+fn scroll_step(adjustment: &gtk4::Adjustment, toward_end: bool) -> bool {
+    let end = adjustment.upper() - adjustment.page_size();
+    let value = adjustment.value();
+    let step = adjustment.page_size() * SMART_SCROLL_STEP;
+    if toward_end {
+        if value >= end - 1.0 {
+            return false;
+        }
+        adjustment.set_value((value + step).min(end));
+    } else {
+        if value <= adjustment.lower() + 1.0 {
+            return false;
+        }
+        adjustment.set_value((value - step).max(adjustment.lower()));
+    }
+    true
+}
+
+fn decoded_texture(image: DecodedImage) -> gtk4::gdk::Texture {
+    let bytes = gtk4::glib::Bytes::from_owned(image.rgba);
+    gtk4::gdk::MemoryTexture::new(
+        image.width as i32,
+        image.height as i32,
+        gtk4::gdk::MemoryFormat::R8g8b8a8,
+        &bytes,
+        (image.width * 4) as usize,
+    ).upcast()
+}
 
-    frames.into_iter().map(|frame| {
-        let (numer, denom) = frame.delay().numer_denom_ms();
-        let delay_ms = if denom == 0 { 100 } else { (numer / denom).max(20) };
-        let buffer = frame.into_buffer();
-        let width = buffer.width();
-        let height = buffer.height();
-        AnimFrame { rgba: buffer.into_raw(), width, height, delay_ms }
-    }).collect()
+fn frame_texture(frame: AnimFrame) -> gtk4::gdk::Texture {
+    let bytes = gtk4::glib::Bytes::from_owned(frame.rgba);
+    gtk4::gdk::MemoryTexture::new(
+        frame.width as i32,
+        frame.height as i32,
+        gtk4::gdk::MemoryFormat::R8g8b8a8,
+        &bytes,
+        (frame.width * 4) as usize,
+    ).upcast()
 }
@@ -1,3 +1,4 @@
 pub mod sidebar;
 pub mod image_view;
 pub mod settings_dialog;
+pub mod password_dialog;
@@ -0,0 +1,185 @@
+use relm4::prelude::*;
+use gtk4::prelude::*;
+use std::path::PathBuf;
+use crate::i18n::{Language, localize};
+
+#[derive(Debug)]
+pub struct PasswordDialogModel {
+    pub is_active: bool,
+    pub archive: Option<PathBuf>,
+    pub incorrect: bool,
+    pub can_remember: bool,
+    pub remember: bool,
+    pub password: gtk4::EntryBuffer,
+    pub language: Language,
+}
+
+#[derive(Debug)]
+pub enum PasswordDialogMsg {
+    Open { archive: PathBuf, incorrect: bool, can_remember: bool, language: Language },
+    Close,
+    Submit,
+    UpdateRemember(bool),
+}
+
+#[derive(Debug)]
+pub enum PasswordDialogOutput {
+    Submit { archive: PathBuf, password: String, remember: bool },
+    Close,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for PasswordDialogModel {
+    type Input = PasswordDialogMsg;
+    type Output = PasswordDialogOutput;
+    type Init = ();
+
+    view! {
+        #[root]
+        gtk4::Window {
+            #[watch]
+            set_title: Some(&localize("Password Required", model.language)),
+            set_default_width: 400,
+            set_resizable: false,
+            set_hide_on_close: true,
+            set_modal: true,
+            #[watch]
+            set_visible: model.is_active,
+
+            connect_close_request[sender] => move |_| {
+                sender.input(PasswordDialogMsg::Close);
+                gtk4::glib::Propagation::Stop
+            },
+
+            gtk4::Box {
+                set_orientation: gtk4::Orientation::Vertical,
+                set_spacing: 10,
+                set_margin_all: 20,
+
+                gtk4::Label {
+                    #[watch]
+                    set_label: &localize("Enter the password for this archive:", model.language),
+                    set_xalign: 0.0,
+                },
+
+                gtk4::Label {
+                    #[watch]
+                    set_label: &model.archive.as_ref()
+                        .and_then(|p| p.file_name())
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    set_xalign: 0.0,
+                    set_ellipsize: gtk4::pango::EllipsizeMode::Middle,
+                    add_css_class: "title-4",
+                },
+
+                gtk4::Entry {
+                    set_buffer: &model.password,
+                    set_visibility: false,
+                    set_input_purpose: gtk4::InputPurpose::Password,
+                    connect_activate => PasswordDialogMsg::Submit,
+                },
+
+                gtk4::Label {
+                    #[watch]
+                    set_label: &localize("Incorrect password. Please try again.", model.language),
+                    #[watch]
+                    set_visible: model.incorrect,
+                    set_xalign: 0.0,
+                    add_css_class: "error",
+                },
+
+                gtk4::CheckButton {
+                    #[watch]
+                    set_label: Some(&localize("Remember Password", model.language)),
+                    #[watch]
+                    set_visible: model.can_remember,
+                    #[watch]
+                    set_active: model.remember,
+                    connect_toggled[sender] => move |btn| {
+                        sender.input(PasswordDialogMsg::UpdateRemember(btn.is_active()));
+                    }
+                },
+
+                gtk4::Label {
+                    #[watch]
+                    set_label: &localize("Remembered passwords are stored unencrypted.", model.language),
+                    #[watch]
+                    set_visible: model.can_remember,
+                    set_xalign: 0.0,
+                    add_css_class: "dim-label",
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_spacing: 10,
+                    set_halign: gtk4::Align::End,
+
+                    gtk4::Button {
+                        #[watch]
+                        set_label: &localize("Cancel", model.language),
+                        connect_clicked => PasswordDialogMsg::Close,
+                    },
+
+                    gtk4::Button {
+                        #[watch]
+                        set_label: &localize("OK", model.language),
+                        add_css_class: "suggested-action",
+                        connect_clicked => PasswordDialogMsg::Submit,
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = PasswordDialogModel {
+            is_active: false,
+            archive: None,
+            incorrect: false,
+            can_remember: true,
+            remember: false,
+            password: gtk4::EntryBuffer::default(),
+            language: Language::default(),
+        };
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
+        match msg {
+            PasswordDialogMsg::Open { archive, incorrect, can_remember, language } => {
+                self.archive = Some(archive);
+                self.incorrect = incorrect;
+                self.can_remember = can_remember;
+                // Asked afresh for every archive, never carried over
+                self.remember = false;
+                self.language = language;
+                self.password.set_text("");
+                self.is_active = true;
+            }
+            PasswordDialogMsg::Close => {
+                self.is_active = false;
+                self.archive = None;
+                self.password.set_text("");
+                let _ = _sender.output(PasswordDialogOutput::Close);
+            }
+            PasswordDialogMsg::Submit => {
+                let Some(archive) = self.archive.take() else { return; };
+                let password = self.password.text().to_string();
+                self.password.set_text("");
+                self.is_active = false;
+                let _ = _sender.output(PasswordDialogOutput::Submit {
+                    archive,
+                    password,
+                    remember: self.can_remember && self.remember,
+                });
+            }
+            PasswordDialogMsg::UpdateRemember(val) => self.remember = val,
+        }
+    }
+}
@@ -22,6 +22,18 @@ pub struct SettingsDialogModel {
     pub loop_images: bool,
     pub single_first_page: bool,
     pub archives_on_top: bool,
+    pub flatten_archives: bool,
+    pub prefetch_ahead: u32,
+    pub prefetch_behind: u32,
+    pub prefetch_memory_mb: u32,
+    pub remember_archive_passwords: bool,
+    pub auto_play_animations: bool,
+    pub color_management: bool,
+    pub downscale_large_images: bool,
+    pub zoom_step_percent: u32,
+    pub min_zoom_percent: u32,
+    pub max_zoom_percent: u32,
+    pub rotate_whole_folder: bool,
     pub input_map: InputMap,
     pub capturing_action: Option<(Action, usize)>,
     pub keyboard_rows: FactoryVecDeque<KeyboardItem>,
@@ -41,6 +53,18 @@ pub enum SettingsDialogMsg {
     UpdateLoopImages(bool),
     UpdateSingleFirstPage(bool),
     UpdateArchivesOnTop(bool),
+    UpdateFlattenArchives(bool),
+    UpdatePrefetchAhead(u32),
+    UpdatePrefetchBehind(u32),
+    UpdatePrefetchMemoryMb(u32),
+    UpdateRememberArchivePasswords(bool),
+    UpdateAutoPlayAnimations(bool),
+    UpdateColorManagement(bool),
+    UpdateDownscaleLargeImages(bool),
+    UpdateZoomStepPercent(u32),
+    UpdateMinZoomPercent(u32),
+    UpdateMaxZoomPercent(u32),
+    UpdateRotateWholeFolder(bool),
     StartCapture(Action, usize),
     CaptureInput(InputSpec),
     ResetKeyboard,
@@ -209,6 +233,16 @@ impl SimpleComponent for SettingsDialogModel {
                         sender.input(SettingsDialogMsg::UpdateArchivesOnTop(btn.is_active()));
                     }
                 },
+
+                gtk4::CheckButton {
+                    #[watch]
+                    set_label: Some(&localize("List Archive Pages Without Folders", model.language)),
+                    #[watch]
+                    set_active: model.flatten_archives,
+                    connect_toggled[sender] => move |btn| {
+                        sender.input(SettingsDialogMsg::UpdateFlattenArchives(btn.is_active()));
+                    }
+                },
                 
                 gtk4::CheckButton {
                     #[watch]
@@ -247,6 +281,8 @@ impl SimpleComponent for SettingsDialogModel {
                         append: (Some("DateDesc"), &localize("Date Desc", model.language)),
                         append: (Some("SizeAsc"), &localize("Size Asc", model.language)),
                         append: (Some("SizeDesc"), &localize("Size Desc", model.language)),
+                        append: (Some("SeriesAsc"), &localize("Series Asc", model.language)),
+                        append: (Some("SeriesDesc"), &localize("Series Desc", model.language)),
                         #[watch]
                         set_active_id: Some(match model.default_dir_sort {
                             SortType::NameAsc => "NameAsc",
@@ -255,6 +291,8 @@ impl SimpleComponent for SettingsDialogModel {
                             SortType::DateDesc => "DateDesc",
                             SortType::SizeAsc => "SizeAsc",
                             SortType::SizeDesc => "SizeDesc",
+                            SortType::SeriesAsc => "SeriesAsc",
+                            SortType::SeriesDesc => "SeriesDesc",
                         }),
                         connect_changed[sender] => move |cb| {
                             if let Some(id) = cb.active_id() {
@@ -265,6 +303,8 @@ impl SimpleComponent for SettingsDialogModel {
                                     "DateDesc" => SortType::DateDesc,
                                     "SizeAsc" => SortType::SizeAsc,
                                     "SizeDesc" => SortType::SizeDesc,
+                                    "SeriesAsc" => SortType::SeriesAsc,
+                                    "SeriesDesc" => SortType::SeriesDesc,
                                     _ => SortType::NameAsc,
                                 };
                                 sender.input(SettingsDialogMsg::UpdateDefaultDirSort(sort));
@@ -297,6 +337,8 @@ impl SimpleComponent for SettingsDialogModel {
                             SortType::DateDesc => "DateDesc",
                             SortType::SizeAsc => "SizeAsc",
                             SortType::SizeDesc => "SizeDesc",
+                            SortType::SeriesAsc => "NameAsc",
+                            SortType::SeriesDesc => "NameDesc",
                         }),
                         connect_changed[sender] => move |cb| {
                             if let Some(id) = cb.active_id() {
@@ -381,6 +423,166 @@ impl SimpleComponent for SettingsDialogModel {
                             sender.input(SettingsDialogMsg::UpdateSingleFirstPage(btn.is_active()));
                         }
                 },
+
+                gtk4::CheckButton {
+                        #[watch]
+                        set_label: Some(&localize("Play Animations Automatically", model.language)),
+                        #[watch]
+                        set_active: model.auto_play_animations,
+                        connect_toggled[sender] => move |btn| {
+                            sender.input(SettingsDialogMsg::UpdateAutoPlayAnimations(btn.is_active()));
+                        }
+                },
+
+                gtk4::CheckButton {
+                        #[watch]
+                        set_label: Some(&localize("Color Management (Convert ICC Profiles to sRGB)", model.language)),
+                        #[watch]
+                        set_active: model.color_management,
+                        connect_toggled[sender] => move |btn| {
+                            sender.input(SettingsDialogMsg::UpdateColorManagement(btn.is_active()));
+                        }
+                },
+
+                gtk4::CheckButton {
+                        #[watch]
+                        set_label: Some(&localize("Downscale Large Images to Window Size", model.language)),
+                        #[watch]
+                        set_active: model.downscale_large_images,
+                        connect_toggled[sender] => move |btn| {
+                            sender.input(SettingsDialogMsg::UpdateDownscaleLargeImages(btn.is_active()));
+                        }
+                },
+
+                gtk4::CheckButton {
+                        #[watch]
+                        set_label: Some(&localize("Rotate and Flip the Whole Folder", model.language)),
+                        #[watch]
+                        set_active: model.rotate_whole_folder,
+                        connect_toggled[sender] => move |btn| {
+                            sender.input(SettingsDialogMsg::UpdateRotateWholeFolder(btn.is_active()));
+                        }
+                },
+
+                gtk4::CheckButton {
+                        #[watch]
+                        set_label: Some(&localize("Remember Archive Passwords", model.language)),
+                        #[watch]
+                        set_tooltip_text: Some(&localize("Remembered passwords are stored unencrypted.", model.language)),
+                        #[watch]
+                        set_active: model.remember_archive_passwords,
+                        connect_toggled[sender] => move |btn| {
+                            sender.input(SettingsDialogMsg::UpdateRememberArchivePasswords(btn.is_active()));
+                        }
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_spacing: 10,
+
+                    gtk4::Label {
+                        #[watch]
+                        set_label: &localize("Prefetch Pages Ahead", model.language),
+                    },
+
+                    gtk4::SpinButton::with_range(0.0, 32.0, 1.0) {
+                        #[watch]
+                        set_value: model.prefetch_ahead as f64,
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(SettingsDialogMsg::UpdatePrefetchAhead(spin.value_as_int() as u32));
+                        }
+                    },
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_spacing: 10,
+
+                    gtk4::Label {
+                        #[watch]
+                        set_label: &localize("Prefetch Pages Behind", model.language),
+                    },
+
+                    gtk4::SpinButton::with_range(0.0, 32.0, 1.0) {
+                        #[watch]
+                        set_value: model.prefetch_behind as f64,
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(SettingsDialogMsg::UpdatePrefetchBehind(spin.value_as_int() as u32));
+                        }
+                    },
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_spacing: 10,
+
+                    gtk4::Label {
+                        #[watch]
+                        set_label: &localize("Image Cache Size (MB)", model.language),
+                    },
+
+                    gtk4::SpinButton::with_range(0.0, 4096.0, 64.0) {
+                        #[watch]
+                        set_value: model.prefetch_memory_mb as f64,
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(SettingsDialogMsg::UpdatePrefetchMemoryMb(spin.value_as_int() as u32));
+                        }
+                    },
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_spacing: 10,
+
+                    gtk4::Label {
+                        #[watch]
+                        set_label: &localize("Zoom Step (%)", model.language),
+                    },
+
+                    gtk4::SpinButton::with_range(1.0, 100.0, 1.0) {
+                        #[watch]
+                        set_value: model.zoom_step_percent as f64,
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(SettingsDialogMsg::UpdateZoomStepPercent(spin.value_as_int() as u32));
+                        }
+                    },
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_spacing: 10,
+
+                    gtk4::Label {
+                        #[watch]
+                        set_label: &localize("Minimum Zoom (%)", model.language),
+                    },
+
+                    gtk4::SpinButton::with_range(1.0, 100.0, 1.0) {
+                        #[watch]
+                        set_value: model.min_zoom_percent as f64,
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(SettingsDialogMsg::UpdateMinZoomPercent(spin.value_as_int() as u32));
+                        }
+                    },
+                },
+
+                gtk4::Box {
+                    set_orientation: gtk4::Orientation::Horizontal,
+                    set_spacing: 10,
+
+                    gtk4::Label {
+                        #[watch]
+                        set_label: &localize("Maximum Zoom (%)", model.language),
+                    },
+
+                    gtk4::SpinButton::with_range(100.0, 10000.0, 100.0) {
+                        #[watch]
+                        set_value: model.max_zoom_percent as f64,
+                        connect_value_changed[sender] => move |spin| {
+                            sender.input(SettingsDialogMsg::UpdateMaxZoomPercent(spin.value_as_int() as u32));
+                        }
+                    },
+                },
             }
         },
 
@@ -500,6 +702,18 @@ impl SimpleComponent for SettingsDialogModel {
             loop_images: false,
             single_first_page: false,
             archives_on_top: true,
+            flatten_archives: false,
+            prefetch_ahead: 4,
+            prefetch_behind: 1,
+            prefetch_memory_mb: 256,
+            remember_archive_passwords: false,
+            auto_play_animations: true,
+            color_management: true,
+            downscale_large_images: false,
+            zoom_step_percent: 5,
+            min_zoom_percent: 1,
+            max_zoom_percent: 3200,
+            rotate_whole_folder: false,
             input_map: InputMap::default(),
             capturing_action: None,
             keyboard_rows,
@@ -595,6 +809,18 @@ impl SimpleComponent for SettingsDialogModel {
                 self.loop_images = settings.loop_images;
                 self.single_first_page = settings.single_first_page;
                 self.archives_on_top = settings.archives_on_top;
+                self.flatten_archives = settings.flatten_archives;
+                self.prefetch_ahead = settings.prefetch_ahead;
+                self.prefetch_behind = settings.prefetch_behind;
+                self.prefetch_memory_mb = settings.prefetch_memory_mb;
+                self.remember_archive_passwords = settings.remember_archive_passwords;
+                self.auto_play_animations = settings.auto_play_animations;
+                self.color_management = settings.color_management;
+                self.downscale_large_images = settings.downscale_large_images;
+                self.zoom_step_percent = settings.zoom_step_percent;
+                self.min_zoom_percent = settings.min_zoom_percent;
+                self.max_zoom_percent = settings.max_zoom_percent;
+                self.rotate_whole_folder = settings.rotate_whole_folder;
                 self.input_map = settings.input_map.clone();
                 self.language = settings.language;
                 self.capturing_action = None;
@@ -618,6 +844,18 @@ impl SimpleComponent for SettingsDialogModel {
                     loop_images: self.loop_images,
                     single_first_page: self.single_first_page,
                     archives_on_top: self.archives_on_top,
+                    flatten_archives: self.flatten_archives,
+                    prefetch_ahead: self.prefetch_ahead,
+                    prefetch_behind: self.prefetch_behind,
+                    prefetch_memory_mb: self.prefetch_memory_mb,
+                    remember_archive_passwords: self.remember_archive_passwords,
+                    auto_play_animations: self.auto_play_animations,
+                    color_management: self.color_management,
+                    downscale_large_images: self.downscale_large_images,
+                    zoom_step_percent: self.zoom_step_percent,
+                    min_zoom_percent: self.min_zoom_percent,
+                    max_zoom_percent: self.max_zoom_percent,
+                    rotate_whole_folder: self.rotate_whole_folder,
                     input_map: self.input_map.clone(),
                     language: self.language,
                 };
@@ -634,6 +872,18 @@ impl SimpleComponent for SettingsDialogModel {
             SettingsDialogMsg::UpdateLoopImages(val) => self.loop_images = val,
             SettingsDialogMsg::UpdateSingleFirstPage(val) => self.single_first_page = val,
             SettingsDialogMsg::UpdateArchivesOnTop(val) => self.archives_on_top = val,
+            SettingsDialogMsg::UpdateFlattenArchives(val) => self.flatten_archives = val,
+            SettingsDialogMsg::UpdatePrefetchAhead(val) => self.prefetch_ahead = val,
+            SettingsDialogMsg::UpdatePrefetchBehind(val) => self.prefetch_behind = val,
+            SettingsDialogMsg::UpdatePrefetchMemoryMb(val) => self.prefetch_memory_mb = val,
+            SettingsDialogMsg::UpdateRememberArchivePasswords(val) => self.remember_archive_passwords = val,
+            SettingsDialogMsg::UpdateAutoPlayAnimations(val) => self.auto_play_animations = val,
+            SettingsDialogMsg::UpdateColorManagement(val) => self.color_management = val,
+            SettingsDialogMsg::UpdateDownscaleLargeImages(val) => self.downscale_large_images = val,
+            SettingsDialogMsg::UpdateZoomStepPercent(val) => self.zoom_step_percent = val,
+            SettingsDialogMsg::UpdateMinZoomPercent(val) => self.min_zoom_percent = val,
+            SettingsDialogMsg::UpdateMaxZoomPercent(val) => self.max_zoom_percent = val,
+            SettingsDialogMsg::UpdateRotateWholeFolder(val) => self.rotate_whole_folder = val,
             
             SettingsDialogMsg::StartCapture(action, slot) => {
                 self.capturing_action = Some((action, slot));
@@ -1026,6 +1276,24 @@ impl FactoryComponent for MouseItem {
                     append: (Some("ToggleRTL"), &Action::ToggleRTL.description(self.language)),
                     append: (Some("PrevPageSingle"), &Action::PrevPageSingle.description(self.language)),
                     append: (Some("NextPageSingle"), &Action::NextPageSingle.description(self.language)),
+                    append: (Some("ToggleAnimation"), &Action::ToggleAnimation.description(self.language)),
+                    append: (Some("NextFrame"), &Action::NextFrame.description(self.language)),
+                    append: (Some("PrevFrame"), &Action::PrevFrame.description(self.language)),
+                    append: (Some("AnimationFaster"), &Action::AnimationFaster.description(self.language)),
+                    append: (Some("AnimationSlower"), &Action::AnimationSlower.description(self.language)),
+                    append: (Some("ToggleContinuous"), &Action::ToggleContinuous.description(self.language)),
+                    append: (Some("FitPage"), &Action::FitPage.description(self.language)),
+                    append: (Some("FitWidth"), &Action::FitWidth.description(self.language)),
+                    append: (Some("FitHeight"), &Action::FitHeight.description(self.language)),
+                    append: (Some("FitOriginal"), &Action::FitOriginal.description(self.language)),
+                    append: (Some("FitWidthCapped"), &Action::FitWidthCapped.description(self.language)),
+                    append: (Some("SmartNextPage"), &Action::SmartNextPage.description(self.language)),
+                    append: (Some("SmartPrevPage"), &Action::SmartPrevPage.description(self.language)),
+                    append: (Some("RotateClockwise"), &Action::RotateClockwise.description(self.language)),
+                    append: (Some("RotateCounterClockwise"), &Action::RotateCounterClockwise.description(self.language)),
+                    append: (Some("Rotate180"), &Action::Rotate180.description(self.language)),
+                    append: (Some("FlipHorizontal"), &Action::FlipHorizontal.description(self.language)),
+                    append: (Some("FlipVertical"), &Action::FlipVertical.description(self.language)),
                     
                     #[watch]
                     set_active_id: Some(self.current_setting.map(|a| format!("{:?}", a)).unwrap_or("None".to_string()).as_str()),
@@ -1046,6 +1314,24 @@ impl FactoryComponent for MouseItem {
                                      "ToggleRTL" => Some(Action::ToggleRTL),
                                      "PrevPageSingle" => Some(Action::PrevPageSingle),
                                      "NextPageSingle" => Some(Action::NextPageSingle),
+                                     "ToggleAnimation" => Some(Action::ToggleAnimation),
+                                     "NextFrame" => Some(Action::NextFrame),
+                                     "PrevFrame" => Some(Action::PrevFrame),
+                                     "AnimationFaster" => Some(Action::AnimationFaster),
+                                     "AnimationSlower" => Some(Action::AnimationSlower),
+                                     "ToggleContinuous" => Some(Action::ToggleContinuous),
+                                     "FitPage" => Some(Action::FitPage),
+                                     "FitWidth" => Some(Action::FitWidth),
+                                     "FitHeight" => Some(Action::FitHeight),
+                                     "FitOriginal" => Some(Action::FitOriginal),
+                                     "FitWidthCapped" => Some(Action::FitWidthCapped),
+                                     "SmartNextPage" => Some(Action::SmartNextPage),
+                                     "SmartPrevPage" => Some(Action::SmartPrevPage),
+                                     "RotateClockwise" => Some(Action::RotateClockwise),
+                                     "RotateCounterClockwise" => Some(Action::RotateCounterClockwise),
+                                     "Rotate180" => Some(Action::Rotate180),
+                                     "FlipHorizontal" => Some(Action::FlipHorizontal),
+                                     "FlipVertical" => Some(Action::FlipVertical),
                                      _ => None,
                                  }
                              };
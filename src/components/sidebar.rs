@@ -1,12 +1,16 @@
 #![allow(unused_assignments)]
 use crate::database::SortType;
 use natord::compare as natural_lexical_cmp;
-use crate::utils::is_supported_image;
+use crate::utils::{is_image_file, is_supported_image};
+use crate::page_source::{self, PageEntry};
+use crate::database::DbHelper;
+use crate::dimension_cache;
+use crate::comic_info;
 
 use relm4::prelude::*;
 use relm4::factory::FactoryVecDeque;
+use std::collections::HashSet;
 use std::path::PathBuf;
-use image::ImageReader;
 use gtk4::prelude::*;
 
 #[derive(Debug)]
@@ -41,6 +45,12 @@ pub struct SidebarModel {
     loop_images: bool,
     single_first_page: bool,
     archives_on_top: bool,
+    flatten_archives: bool,
+    prefetch_ahead: usize,
+    prefetch_behind: usize,
+    db_helper: Option<DbHelper>,
+    // Pages flagged as DoublePage in ComicInfo.xml, always shown alone in spread mode
+    double_pages: HashSet<PathBuf>,
 }
 
 #[derive(Debug)]
@@ -52,8 +62,10 @@ pub enum SidebarMsg {
 
     UpdateDirSort(SortType),
     ChangeImageSort(SortType),
+    Refresh,
     UpdateSpreadMode(bool),
     GetSpreadPages(PathBuf),
+    GetAllPages(PathBuf),
     OpenNextImage(PathBuf),
     OpenPrevImage(PathBuf),
     OpenNextImageSingle(PathBuf),
@@ -62,6 +74,11 @@ pub enum SidebarMsg {
     UpdateLoopImages(bool),
     UpdateSingleFirstPage(bool),
     UpdateArchivesOnTop(bool),
+    UpdateFlattenArchives(bool),
+    UpdatePrefetch { ahead: usize, behind: usize },
+    PrefetchAround(Vec<PathBuf>),
+    SetDbHelper(Option<DbHelper>),
+    UpdateDoublePages(Vec<PathBuf>),
     OpenFirstImage,
     ScrollToSelection,
 }
@@ -81,11 +98,15 @@ pub enum SidebarOutput {
     OpenImage(PathBuf),
     // OpenDirectory(PathBuf),
     SpreadPages(Vec<PathBuf>),
+    // Every page of the listing, for the continuous view, and the one to bring into view
+    AllPages { pages: Vec<PathBuf>, current: PathBuf },
     PathChanged(String),
     ImageSortChanged(SortType),
     ClearImage,
     RequestNextDir,
     RequestPrevDir,
+    Prefetch(Vec<PathBuf>),
+    PasswordRequired(PathBuf),
 }
 
 #[relm4::factory(pub)]
@@ -296,6 +317,11 @@ impl SimpleComponent for SidebarModel {
             loop_images: false,
             single_first_page: false,
             archives_on_top: true,
+            flatten_archives: false,
+            prefetch_ahead: 0,
+            prefetch_behind: 0,
+            db_helper: None,
+            double_pages: HashSet::new(),
         };
         
         let _initial_path = model.current_path.clone();
@@ -342,9 +368,26 @@ impl SimpleComponent for SidebarModel {
                 // Removed eager OpenImage
             }
             SidebarMsg::OpenDirectory(path) => {
-                let is_archive = path.extension().and_then(|s| s.to_str()).map_or(false, |ext| ext.eq_ignore_ascii_case("zip"));
+                // Archives and their inner folders are previewed next to their siblings, unless
+                // they hold sub-folders themselves: then they are entered like a directory.
+                let folder = page_source::archive_folder(&path);
+                let in_archive = folder.is_some();
+                // Same test as the folder list of scan_directory, without sorting anything;
+                // the listing itself is built once, by refresh_view
+                let has_folders = folder.is_some_and(|folder| {
+                    page_source::with_source(&folder.container, |source| source.entries())
+                        .map(|entries| {
+                            let (folders, files) = if self.flatten_archives {
+                                (Vec::new(), entries)
+                            } else {
+                                page_source::folder_listing(entries, &folder.entry)
+                            };
+                            !folders.is_empty() || files.iter().any(|e| page_source::is_archive(std::path::Path::new(&e.name)))
+                        })
+                        .unwrap_or(false)
+                });
                 
-                if is_archive {
+                if in_archive && !has_folders {
                     self.preview_archive_path = Some(path.clone());
                     self.selected_dir_path = Some(path.clone());
                     
@@ -397,6 +440,9 @@ impl SimpleComponent for SidebarModel {
                     let _ = _sender.output(SidebarOutput::ImageSortChanged(sort));
                 }
             }
+            SidebarMsg::Refresh => {
+                self.refresh_view();
+            }
             SidebarMsg::UpdateSpreadMode(is_spread) => {
                 self.spread_view = is_spread;
             }
@@ -549,6 +595,10 @@ impl SimpleComponent for SidebarModel {
                      }
                  }
             }
+             SidebarMsg::GetAllPages(current) => {
+                 let pages = self.images.iter().map(|item| item.path.clone()).collect();
+                 let _ = _sender.output(SidebarOutput::AllPages { pages, current });
+             }
              SidebarMsg::SelectImage(path) => {
                  self.selected_path = Some(path);
                  self.images.broadcast(ImageItemMsg::UpdateSelection(self.selected_path.clone()));
@@ -564,7 +614,34 @@ impl SimpleComponent for SidebarModel {
                  self.archives_on_top = val;
                  self.refresh_view();
              }
+             SidebarMsg::UpdateFlattenArchives(val) => {
+                 if self.flatten_archives != val {
+                     self.flatten_archives = val;
+                     self.refresh_view();
+                 }
+             }
+             SidebarMsg::UpdateDoublePages(pages) => {
+                 self.double_pages = pages.into_iter().collect();
+             }
+             SidebarMsg::UpdatePrefetch { ahead, behind } => {
+                 self.prefetch_ahead = ahead;
+                 self.prefetch_behind = behind;
+             }
+             SidebarMsg::SetDbHelper(helper) => {
+                 self.db_helper = helper;
+             }
+             SidebarMsg::PrefetchAround(shown) => {
+                 let paths = self.prefetch_candidates(&shown);
+                 if !paths.is_empty() {
+                     let _ = _sender.output(SidebarOutput::Prefetch(paths));
+                 }
+             }
              SidebarMsg::OpenFirstImage => {
+                 // Ask for the password before the first page tries (and fails) to load
+                 let container = self.preview_archive_path.as_ref().unwrap_or(&self.current_path);
+                 if page_source::needs_password(container) {
+                     let _ = _sender.output(SidebarOutput::PasswordRequired(container.clone()));
+                 }
                  if let Some(first) = self.images.get(0) {
                      let _ = _sender.output(SidebarOutput::OpenImage(first.path.clone()));
                      self.selected_path = Some(first.path.clone());
@@ -581,6 +658,32 @@ impl SimpleComponent for SidebarModel {
 }
 
 impl SidebarModel {
+    /// Pages to read ahead of what is on screen, nearest first: the next pages in reading
+    /// order (which also covers the partner of an upcoming spread), then the previous ones.
+    fn prefetch_candidates(&self, shown: &[PathBuf]) -> Vec<PathBuf> {
+        let indices: Vec<usize> = self.images.iter()
+            .enumerate()
+            .filter(|(_, item)| shown.contains(&item.path))
+            .map(|(i, _)| i)
+            .collect();
+        let (Some(&first), Some(&last)) = (indices.first(), indices.last()) else {
+            return Vec::new();
+        };
+
+        let mut paths = Vec::new();
+        for i in (last + 1)..(last + 1 + self.prefetch_ahead) {
+            if let Some(item) = self.images.get(i) {
+                paths.push(item.path.clone());
+            }
+        }
+        for i in (first.saturating_sub(self.prefetch_behind)..first).rev() {
+            if let Some(item) = self.images.get(i) {
+                paths.push(item.path.clone());
+            }
+        }
+        paths
+    }
+
     fn scroll_to_selected_directory(&self) {
         if let Some(sw) = &self.directories_scrolled_window {
             let mut found_idx = None;
@@ -653,56 +756,19 @@ impl SidebarModel {
         }
     }
 
-    fn is_portrait(&self, path: &PathBuf) -> bool {
+    fn is_portrait(&self, path: &std::path::Path) -> bool {
+        if self.double_pages.contains(path) {
+            return false;
+        }
         if let Some((w, h)) = self.get_image_dimensions(path) {
             return h > w;
         }
         false
     }
     
-    fn get_image_dimensions(&self, path: &PathBuf) -> Option<(u32, u32)> {
-        use std::io::Read;
-        if path.exists() && path.is_file() {
-            if let Ok(reader) = ImageReader::open(path) {
-                if let Ok(reader) = reader.with_guessed_format() {
-                     if let Ok(dim) = reader.into_dimensions() {
-                         return Some(dim);
-                     }
-                }
-            }
-        } else {
-         // Zip handling
-         let mut current = path.clone();
-         while let Some(parent) = current.parent() {
-             if parent.is_file() {
-                 if let Some(ext) = parent.extension().and_then(|s| s.to_str()) {
-                     if ext.to_lowercase() == "zip" {
-                         if let Ok(suffix) = path.strip_prefix(parent) {
-                             let entry_name = suffix.to_string_lossy();
-                             if let Ok(file) = std::fs::File::open(parent) {
-                                  if let Ok(mut archive) = zip::ZipArchive::new(file) {
-                                      if let Ok(mut entry) = archive.by_name(&entry_name) {
-                                          let mut buffer = Vec::new();
-                                          if entry.read_to_end(&mut buffer).is_ok() {
-                                              if let Ok(reader) = ImageReader::new(std::io::Cursor::new(buffer)).with_guessed_format() {
-                                                  if let Ok(dim) = reader.into_dimensions() {
-                                                      return Some(dim);
-                                                  }
-                                              }
-                                          }
-                                      }
-                                  }
-                             }
-                         }
-                     }
-                 }
-                 break;
-             }
-             current = parent.to_path_buf();
-         }
+    fn get_image_dimensions(&self, path: &std::path::Path) -> Option<(u32, u32)> {
+        dimension_cache::get(path)
     }
-    None
-}
 
     fn refresh_view(&mut self) {
         self.reload_directories();
@@ -726,6 +792,9 @@ impl SidebarModel {
     fn reload_images(&mut self) {
         let image_source = self.preview_archive_path.as_ref().unwrap_or(&self.current_path);
         let (_, img_entries) = self.scan_directory(image_source);
+        if !img_entries.is_empty() {
+            dimension_cache::warm_up(image_source.clone(), self.db_helper.clone());
+        }
         
         let mut imgs = self.images.guard();
         imgs.clear();
@@ -734,58 +803,94 @@ impl SidebarModel {
         }
     }
 
-    fn scan_directory(&self, path: &PathBuf) -> (Vec<(String, PathBuf, bool)>, Vec<(String, PathBuf)>) {
-        scan_directory_custom(path, &self.dir_sort, &self.image_sort, self.archives_on_top)
+    fn scan_directory(&self, path: &PathBuf) -> DirListing {
+        scan_directory_custom(path, &self.dir_sort, &self.image_sort, self.archives_on_top, self.flatten_archives)
     }
 
 }
+/// Directory entries as (name, path, is_archive) and image entries as (name, path).
+pub type DirListing = (Vec<(String, PathBuf, bool)>, Vec<(String, PathBuf)>);
+
 pub fn scan_directory_custom(
     path: &PathBuf, 
     dir_sort: &SortType, 
     image_sort: &SortType, 
-    archives_on_top: bool
-) -> (Vec<(String, PathBuf, bool)>, Vec<(String, PathBuf)>) {
+    archives_on_top: bool,
+    flatten_archives: bool,
+) -> DirListing {
          let mut dir_entries = Vec::new();
          let mut img_entries = Vec::new();
 
-        if path.is_file() {
-            // Check for ZIP
-             if let Some(ext) = path.extension().and_then(|s| s.to_str()).map(|s| s.to_lowercase()) {
-                 if ext == "zip" {
-                     if let Ok(file) = std::fs::File::open(&path) {
-                         if let Ok(mut archive) = zip::ZipArchive::new(file) {
-                             let mut raw_entries = Vec::new();
-                             for i in 0..archive.len() {
-                                 if let Ok(file) = archive.by_index(i) {
-                                     let name = file.name().to_string();
-                                     raw_entries.push(name);
-                                 }
-                             }
-                             
-                             for name in raw_entries {
-                                 // Check extension of entry
-                                 if is_supported_image(std::path::Path::new(&name)) {
-                                     let entry_path = path.join(&name);
-                                     img_entries.push((name, entry_path));
-                                 }
-                             }
-                             // Sort Images in Archive
-                             match image_sort {
-                                SortType::NameAsc => img_entries.sort_by(|a, b| natural_lexical_cmp(&a.0, &b.0)),
-                                SortType::NameDesc => { img_entries.sort_by(|a, b| natural_lexical_cmp(&a.0, &b.0)); img_entries.reverse(); },
-                                SortType::DateAsc | SortType::DateDesc | SortType::SizeAsc | SortType::SizeDesc => {
-                                    // Zip entries don't easily support metadata access without costly lookups.
-                                    // Default to name sort for now or implement if needed.
-                                    img_entries.sort_by(|a, b| natural_lexical_cmp(&a.0, &b.0));
-                                     if matches!(image_sort, SortType::DateDesc | SortType::SizeDesc) {
-                                         img_entries.reverse();
-                                     }
-                                }
-                             }
-                         }
-                     }
-                 }
-             }
+        if let Some(folder) = page_source::archive_folder(path) {
+            // Archives (or folders inside them): list entries through the page source
+            match page_source::with_source(&folder.container, |source| source.entries()) {
+                Ok(entries) => {
+                    let (folders, files) = if flatten_archives {
+                        (Vec::new(), entries.clone())
+                    } else {
+                        page_source::folder_listing(entries.clone(), &folder.entry)
+                    };
+                    // Archives inside the archive are listed like folders
+                    let (inner_archives, files): (Vec<PageEntry>, Vec<PageEntry>) = files.into_iter()
+                        .partition(|e| page_source::is_archive(std::path::Path::new(&e.name)));
+                    let mut pages: Vec<PageEntry> = files.into_iter()
+                        .filter(|e| is_supported_image(std::path::Path::new(&e.name)))
+                        .collect();
+                    // Sort Images in Archive
+                    match image_sort {
+                        SortType::NameAsc => pages.sort_by(|a, b| natural_lexical_cmp(&a.name, &b.name)),
+                        SortType::NameDesc => { pages.sort_by(|a, b| natural_lexical_cmp(&a.name, &b.name)); pages.reverse(); },
+                        SortType::DateAsc => pages.sort_by_key(|e| e.modified),
+                        SortType::DateDesc => { pages.sort_by_key(|e| e.modified); pages.reverse(); },
+                        SortType::SizeAsc => pages.sort_by_key(|e| e.size),
+                        SortType::SizeDesc => { pages.sort_by_key(|e| e.size); pages.reverse(); },
+                        // Pages carry no series of their own
+                        SortType::SeriesAsc => pages.sort_by(|a, b| natural_lexical_cmp(&a.name, &b.name)),
+                        SortType::SeriesDesc => { pages.sort_by(|a, b| natural_lexical_cmp(&a.name, &b.name)); pages.reverse(); },
+                    }
+                    for page in pages {
+                        let entry_path = folder.container.join(&page.name);
+                        let name = if flatten_archives {
+                            page.name
+                        } else {
+                            page.name.rsplit('/').next().unwrap_or(&page.name).to_string()
+                        };
+                        img_entries.push((name, entry_path));
+                    }
+
+                    // Sort Folders in Archive. Date and size are taken from the pages they hold.
+                    let folder_stats = |name: &str| {
+                        let inner = if folder.entry.is_empty() { format!("{}/", name) } else { format!("{}/{}/", folder.entry, name) };
+                        let children = entries.iter().filter(|e| e.name.starts_with(&inner));
+                        children.fold((None, 0u64), |(modified, size), e| (modified.max(e.modified), size + e.size))
+                    };
+                    let mut folders: Vec<_> = folders.into_iter()
+                        .map(|name| { let stats = folder_stats(&name); let folder_path = path.join(&name); (name, stats, folder_path, false) })
+                        .collect();
+                    for archive in inner_archives {
+                        let name = archive.name.rsplit('/').next().unwrap_or(&archive.name).to_string();
+                        folders.push((name, (archive.modified, archive.size), folder.container.join(&archive.name), true));
+                    }
+                    match dir_sort {
+                        SortType::NameAsc => folders.sort_by(|a, b| natural_lexical_cmp(&a.0, &b.0)),
+                        SortType::NameDesc => { folders.sort_by(|a, b| natural_lexical_cmp(&a.0, &b.0)); folders.reverse(); },
+                        SortType::DateAsc => folders.sort_by_key(|f| f.1.0),
+                        SortType::DateDesc => { folders.sort_by_key(|f| f.1.0); folders.reverse(); },
+                        SortType::SizeAsc => folders.sort_by_key(|f| f.1.1),
+                        SortType::SizeDesc => { folders.sort_by_key(|f| f.1.1); folders.reverse(); },
+                        // Only nested archives can hold their own ComicInfo.xml, and those are
+                        // not read for sorting (see comic_info::load_for_listing)
+                        SortType::SeriesAsc => sort_by_series(&mut folders, |f| series_key(&f.0, f.3.then_some(f.2.as_path()))),
+                        SortType::SeriesDesc => { sort_by_series(&mut folders, |f| series_key(&f.0, f.3.then_some(f.2.as_path()))); folders.reverse(); },
+                    }
+                    // Then stable sort by is_archive vs is_dir based on setting, as for directories on disk
+                    folders.sort_by_key(|f| f.3 != archives_on_top);
+                    for (name, _, folder_path, is_archive) in folders {
+                        dir_entries.push((name, folder_path, is_archive));
+                    }
+                }
+                Err(e) => eprintln!("Failed to read archive {}: {}", path.display(), e),
+            }
         } else if let Ok(entries) = std::fs::read_dir(&path) {
             for entry in entries.flatten() {
                 let path = entry.path();
@@ -796,21 +901,16 @@ pub fn scan_directory_custom(
                          }
                     }
                 } else if path.is_file() {
-                    // Check extension
-                    if is_supported_image(&path) {
+                    if page_source::is_container_file(&path) {
+                        // Archives, documents and multi-page TIFFs are treated as directories
+                        if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
+                            dir_entries.push((name.to_string(), path, true));
+                        }
+                    } else if is_image_file(&path) {
+                         // By extension, or by content for misnamed files
                          if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
                              img_entries.push((name.to_string(), path));
                          }
-                    } else if let Some(ext) = path.extension().and_then(|s| s.to_str()).map(|s| s.to_lowercase()) {
-                        match ext.as_str() {
-                            "zip" => {
-                                // Archive is treated as directory
-                                if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
-                                    dir_entries.push((name.to_string(), path, true));
-                                }
-                            }
-                            _ => {}
-                        }
                     }
                 }
             }
@@ -824,6 +924,8 @@ pub fn scan_directory_custom(
                  SortType::DateDesc => { dir_entries.sort_by_cached_key(|a| std::fs::metadata(&a.1).and_then(|m| m.modified()).ok()); dir_entries.reverse(); },
                  SortType::SizeAsc => dir_entries.sort_by_cached_key(|a| std::fs::metadata(&a.1).map(|m| m.len()).unwrap_or(0)),
                  SortType::SizeDesc => { dir_entries.sort_by_cached_key(|a| std::fs::metadata(&a.1).map(|m| m.len()).unwrap_or(0)); dir_entries.reverse(); },
+                 SortType::SeriesAsc => sort_by_series(&mut dir_entries, |a| series_key(&a.0, Some(&a.1))),
+                 SortType::SeriesDesc => { sort_by_series(&mut dir_entries, |a| series_key(&a.0, Some(&a.1))); dir_entries.reverse(); },
             }
             // Then stable sort by is_archive vs is_dir based on setting
             if archives_on_top {
@@ -854,6 +956,8 @@ pub fn scan_directory_custom(
                  SortType::DateDesc => { img_entries.sort_by_cached_key(|a| std::fs::metadata(&a.1).and_then(|m| m.modified()).ok()); img_entries.reverse(); },
                  SortType::SizeAsc => img_entries.sort_by_cached_key(|a| std::fs::metadata(&a.1).map(|m| m.len()).unwrap_or(0)),
                  SortType::SizeDesc => { img_entries.sort_by_cached_key(|a| std::fs::metadata(&a.1).map(|m| m.len()).unwrap_or(0)); img_entries.reverse(); },
+                 SortType::SeriesAsc => img_entries.sort_by(|a, b| natural_lexical_cmp(&a.0, &b.0)),
+                 SortType::SeriesDesc => { img_entries.sort_by(|a, b| natural_lexical_cmp(&a.0, &b.0)); img_entries.reverse(); },
             }
         }
         (dir_entries, img_entries)
@@ -861,3 +965,17 @@ pub fn scan_directory_custom(
 
 
 
+
+/// Sort key from ComicInfo.xml as (series, number), falling back to the entry name.
+fn series_key(name: &str, container: Option<&std::path::Path>) -> (String, String) {
+    let info = container.and_then(comic_info::load_for_listing);
+    let series = info.as_ref().and_then(|i| i.series.clone()).unwrap_or_else(|| name.to_string());
+    let number = info.and_then(|i| i.number).unwrap_or_default();
+    (series, number)
+}
+
+fn sort_by_series<T>(items: &mut Vec<T>, key: impl Fn(&T) -> (String, String)) {
+    let mut keyed: Vec<_> = items.drain(..).map(|item| (key(&item), item)).collect();
+    keyed.sort_by(|(a, _), (b, _)| natural_lexical_cmp(&a.0, &b.0).then_with(|| natural_lexical_cmp(&a.1, &b.1)));
+    items.extend(keyed.into_iter().map(|(_, item)| item));
+}
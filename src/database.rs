@@ -12,10 +12,97 @@ pub enum SortType {
     DateDesc,
     SizeAsc,
     SizeDesc,
+    SeriesAsc,
+    SeriesDesc,
+}
+
+/// How pages are scaled to the window.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FitMode {
+    #[default]
+    Page,
+    Width,
+    Height,
+    Original,
+    // Fit width, but never enlarged beyond 100%
+    WidthCapped,
+}
+
+impl FitMode {
+    pub fn variants() -> &'static [FitMode] {
+        &[
+            FitMode::Page,
+            FitMode::Width,
+            FitMode::Height,
+            FitMode::Original,
+            FitMode::WidthCapped,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FitMode::Page => "Fit Page",
+            FitMode::Width => "Fit Width",
+            FitMode::Height => "Fit Height",
+            FitMode::Original => "100%",
+            FitMode::WidthCapped => "Fit Width (max 100%)",
+        }
+    }
+}
+
+/// Rotation and mirroring of a page on screen. The page is mirrored first, then turned.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PageTransform {
+    // Clockwise, in quarter turns (0 to 3)
+    #[serde(default)]
+    pub quarter_turns: u8,
+    // Flipped left to right
+    #[serde(default)]
+    pub mirrored: bool,
+}
+
+impl PageTransform {
+    pub fn is_identity(&self) -> bool {
+        self.quarter_turns.is_multiple_of(4) && !self.mirrored
+    }
+
+    /// Width and height trade places.
+    pub fn swaps_axes(&self) -> bool {
+        self.quarter_turns % 2 == 1
+    }
+
+    /// Size of a `width` by `height` page once transformed.
+    pub fn oriented_size(&self, (width, height): (u32, u32)) -> (u32, u32) {
+        if self.swaps_axes() { (height, width) } else { (width, height) }
+    }
+
+    pub fn rotated(self, quarter_turns: u8) -> Self {
+        Self { quarter_turns: (self.quarter_turns + quarter_turns) % 4, ..self }
+    }
+
+    /// Flip what is on screen left to right. Mirroring after a turn equals mirroring
+    /// first and turning the other way.
+    pub fn flipped_horizontally(self) -> Self {
+        Self { quarter_turns: (4 - self.quarter_turns % 4) % 4, mirrored: !self.mirrored }
+    }
+
+    /// Flip what is on screen upside down: a horizontal flip and a half turn.
+    pub fn flipped_vertically(self) -> Self {
+        self.flipped_horizontally().rotated(2)
+    }
+}
+
+/// Transform of a single page, overriding the one of its directory.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PageRotation {
+    // Path relative to the directory or archive
+    pub name: String,
+    pub transform: PageTransform,
 }
 
 use crate::input_settings::InputMap;
 use crate::i18n::Language;
+use crate::page_source::NameEncoding;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DirectorySettings {
@@ -28,7 +115,35 @@ pub struct DirectorySettings {
     pub dir_sort: SortType,
     #[serde(default)]
     pub image_sort: SortType,
+    #[serde(default)]
+    pub name_encoding: NameEncoding,
+    #[serde(default)]
+    pub continuous_view: bool,
+    #[serde(default)]
+    pub fit_mode: FitMode,
+    #[serde(default)]
+    pub transform: PageTransform,
+    #[serde(default)]
+    pub page_transforms: Vec<PageRotation>,
+}
+
+impl DirectorySettings {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            spread_view: false,
+            right_to_left: true,
+            dir_sort: SortType::default(),
+            image_sort: SortType::default(),
+            name_encoding: NameEncoding::default(),
+            continuous_view: false,
+            fit_mode: FitMode::default(),
+            transform: PageTransform::default(),
+            page_transforms: Vec::new(),
+        }
+    }
 }
+
 fn default_false() -> bool { false }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -51,10 +166,62 @@ pub struct AppSettings {
     pub single_first_page: bool,
     #[serde(default = "default_true")]
     pub archives_on_top: bool,
+    #[serde(default = "default_false")]
+    pub flatten_archives: bool,
     #[serde(default)]
     pub input_map: InputMap,
     #[serde(default)]
     pub language: Language,
+    #[serde(default = "default_prefetch_ahead")]
+    pub prefetch_ahead: u32,
+    #[serde(default = "default_prefetch_behind")]
+    pub prefetch_behind: u32,
+    #[serde(default = "default_prefetch_memory_mb")]
+    pub prefetch_memory_mb: u32,
+    // Opt-in: remembered passwords are stored in the database as plain text
+    #[serde(default)]
+    pub remember_archive_passwords: bool,
+    #[serde(default = "default_true")]
+    pub auto_play_animations: bool,
+    #[serde(default = "default_true")]
+    pub color_management: bool,
+    #[serde(default)]
+    pub downscale_large_images: bool,
+    #[serde(default = "default_zoom_step_percent")]
+    pub zoom_step_percent: u32,
+    #[serde(default = "default_min_zoom_percent")]
+    pub min_zoom_percent: u32,
+    #[serde(default = "default_max_zoom_percent")]
+    pub max_zoom_percent: u32,
+    #[serde(default)]
+    pub rotate_whole_folder: bool,
+}
+
+/// Cached pixel sizes of the pages in one directory or archive.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PageDimensions {
+    pub container: String, // Key
+    #[serde(default)]
+    pub pages: Vec<PageDimension>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PageDimension {
+    pub name: String,
+    // Size and mtime (unix seconds) of the entry when measured; a mismatch means it changed.
+    pub size: i64,
+    pub modified: i64,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A password the user chose to remember for an encrypted archive. Stored unencrypted,
+/// so only written when the user opts in. Kept in its own collection so it never travels
+/// with `AppSettings`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchivePassword {
+    pub path: String, // Key
+    pub password: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -66,6 +233,12 @@ pub struct AppState {
 
 fn default_key() -> String { "global".to_string() }
 fn default_true() -> bool { true }
+fn default_prefetch_ahead() -> u32 { 4 }
+fn default_prefetch_behind() -> u32 { 1 }
+fn default_prefetch_memory_mb() -> u32 { 256 }
+fn default_zoom_step_percent() -> u32 { 5 }
+fn default_min_zoom_percent() -> u32 { 1 }
+fn default_max_zoom_percent() -> u32 { 3200 }
 
 impl Default for AppSettings {
     fn default() -> Self {
@@ -79,12 +252,25 @@ impl Default for AppSettings {
             loop_images: false,
             single_first_page: false,
             archives_on_top: true,
+            flatten_archives: false,
             input_map: InputMap::default(),
             language: Language::default(),
+            prefetch_ahead: default_prefetch_ahead(),
+            prefetch_behind: default_prefetch_behind(),
+            prefetch_memory_mb: default_prefetch_memory_mb(),
+            remember_archive_passwords: false,
+            auto_play_animations: true,
+            color_management: true,
+            downscale_large_images: false,
+            zoom_step_percent: default_zoom_step_percent(),
+            min_zoom_percent: default_min_zoom_percent(),
+            max_zoom_percent: default_max_zoom_percent(),
+            rotate_whole_folder: false,
         }
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct DbHelper {
     path: PathBuf,
 }
@@ -189,4 +375,46 @@ impl DbHelper {
         }
         Ok(())
     }
+
+    pub fn get_archive_password(&self, path: &str) -> Result<Option<ArchivePassword>> {
+        let Some(db) = self.try_open() else { return Ok(None); };
+        let collection = db.collection::<ArchivePassword>("archive_passwords");
+        Ok(collection.find_one(polodb_core::bson::doc! { "path": path })?)
+    }
+
+    pub fn save_archive_password(&self, entry: &ArchivePassword) -> Result<()> {
+        let Some(db) = self.try_open() else { return Ok(()); };
+        let collection = db.collection::<ArchivePassword>("archive_passwords");
+        collection.delete_many(polodb_core::bson::doc! { "path": &entry.path })?;
+        collection.insert_one(entry.clone())?;
+        Ok(())
+    }
+
+    pub fn clear_archive_passwords(&self) -> Result<()> {
+        let Some(db) = self.try_open() else { return Ok(()); };
+        let collection = db.collection::<ArchivePassword>("archive_passwords");
+        collection.delete_many(polodb_core::bson::doc! {})?;
+        Ok(())
+    }
+
+    pub fn get_page_dimensions(&self, container: &str) -> Result<Option<PageDimensions>> {
+        let Some(db) = self.try_open() else { return Ok(None); };
+        let collection = db.collection::<PageDimensions>("page_dimensions");
+        Ok(collection.find_one(polodb_core::bson::doc! { "container": container })?)
+    }
+
+    pub fn save_page_dimensions(&self, dimensions: &PageDimensions) -> Result<()> {
+        let Some(db) = self.try_open() else { return Ok(()); };
+        let collection = db.collection::<PageDimensions>("page_dimensions");
+        let doc = polodb_core::bson::to_document(dimensions)?;
+        let mut update_doc = polodb_core::bson::Document::new();
+        update_doc.insert("$set", doc);
+
+        if collection.find_one(polodb_core::bson::doc! { "container": &dimensions.container })?.is_none() {
+            collection.insert_one(dimensions.clone())?;
+        } else {
+            let _ = collection.update_one(polodb_core::bson::doc! { "container": &dimensions.container }, update_doc);
+        }
+        Ok(())
+    }
 }
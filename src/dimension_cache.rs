@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use image::ImageReader;
+use once_cell::sync::Lazy;
+use crate::codecs;
+use crate::database::{DbHelper, PageDimension, PageDimensions};
+use crate::page_source::{self, FileStamp, PageEntry};
+use crate::utils::is_supported_image;
+
+/// Bytes read from the start of a page to measure it. Enough for the header of every
+/// supported format unless large metadata comes first; such pages are measured when shown.
+const HEAD_LIMIT: usize = 256 * 1024;
+
+/// Upper bound on remembered page sizes; the cache starts over when it is exceeded.
+const MAX_DIMENSIONS: usize = 50_000;
+
+// Page path -> (stamp of the file it was read from, (width, height)),
+// shared between the sidebar, the image view and the background worker.
+type Dimensions = HashMap<PathBuf, (FileStamp, (u32, u32))>;
+static DIMENSIONS: Lazy<Mutex<Dimensions>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// Bumped for every listing; the worker drops a listing as soon as a newer one arrives.
+static LATEST: AtomicU64 = AtomicU64::new(0);
+
+struct WarmUp {
+    generation: u64,
+    container: PathBuf,
+    db_helper: Option<DbHelper>,
+}
+
+static WORKER: Lazy<Mutex<Sender<WarmUp>>> = Lazy::new(|| {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || run_worker(receiver));
+    Mutex::new(sender)
+});
+
+/// Dimensions of a page, from the cache when known, otherwise read from the image header.
+pub fn get(path: &Path) -> Option<(u32, u32)> {
+    let stamp = FileStamp::of(path).ok()?;
+    if let Some(dim) = cached(path, stamp) {
+        return Some(dim);
+    }
+    let dim = read_dimensions(path)?;
+    remember(path.to_path_buf(), stamp, dim);
+    Some(dim)
+}
+
+/// Dimensions of a page if already known, without reading anything.
+pub fn lookup(path: &Path) -> Option<(u32, u32)> {
+    cached(path, FileStamp::of(path).ok()?)
+}
+
+/// Drop the sizes of PDF pages, which follow the box pages are rendered into.
+pub fn forget_pdf_pages() {
+    DIMENSIONS.lock().unwrap_or_else(|e| e.into_inner()).retain(|path, _| !page_source::is_pdf_page(path));
+}
+
+/// Measure every page of a directory or archive on the background worker, replacing
+/// whatever listing it was still measuring. Stored values are reused when the entry's
+/// size and mtime are unchanged.
+pub fn warm_up(container: PathBuf, db_helper: Option<DbHelper>) {
+    let generation = LATEST.fetch_add(1, Ordering::SeqCst) + 1;
+    let job = WarmUp { generation, container, db_helper };
+    if WORKER.lock().unwrap_or_else(|e| e.into_inner()).send(job).is_err() {
+        eprintln!("Page dimension worker is not running");
+    }
+}
+
+fn cached(path: &Path, stamp: FileStamp) -> Option<(u32, u32)> {
+    DIMENSIONS.lock().unwrap_or_else(|e| e.into_inner())
+        .get(path)
+        .filter(|(cached_stamp, _)| *cached_stamp == stamp)
+        .map(|(_, dim)| *dim)
+}
+
+fn remember(path: PathBuf, stamp: FileStamp, dim: (u32, u32)) {
+    let mut dimensions = DIMENSIONS.lock().unwrap_or_else(|e| e.into_inner());
+    if dimensions.len() >= MAX_DIMENSIONS && !dimensions.contains_key(&path) {
+        dimensions.clear();
+    }
+    dimensions.insert(path, (stamp, dim));
+}
+
+fn run_worker(jobs: Receiver<WarmUp>) {
+    while let Ok(mut job) = jobs.recv() {
+        // Listings queued up while the last one was measured are already stale
+        while let Ok(newer) = jobs.try_recv() {
+            job = newer;
+        }
+        measure(&job);
+    }
+}
+
+fn is_cancelled(job: &WarmUp) -> bool {
+    LATEST.load(Ordering::SeqCst) != job.generation
+}
+
+fn measure(job: &WarmUp) {
+    // Folders inside an archive are covered by measuring the whole archive
+    let container = page_source::archive_folder(&job.container).map(|f| f.container).unwrap_or_else(|| job.container.clone());
+    // A private handle, so pages being shown never wait on the shared one
+    let mut source = match page_source::open(&container) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Failed to open {} for dimensions: {}", container.display(), e);
+            return;
+        }
+    };
+    let entries = match source.entries() {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Failed to list {} for dimensions: {}", container.display(), e);
+            return;
+        }
+    };
+    let key = container.to_string_lossy().to_string();
+    let stored = job.db_helper.as_ref()
+        .and_then(|helper| helper.get_page_dimensions(&key).ok().flatten())
+        .map(|d| d.pages)
+        .unwrap_or_default();
+    let stored: HashMap<&str, &PageDimension> = stored.iter().map(|p| (p.name.as_str(), p)).collect();
+
+    let mut pages = Vec::new();
+    let mut changed = false;
+    let mut names = Vec::new();
+    let mut unmeasured: HashMap<&str, &PageEntry> = HashMap::new();
+    for entry in entries.iter().filter(|e| is_supported_image(Path::new(&e.name))) {
+        if is_cancelled(job) {
+            return;
+        }
+        let path = container.join(&entry.name);
+        let Ok(stamp) = FileStamp::of(&path) else { continue; };
+        if page_source::is_pdf_page(&path) {
+            // Cheap to work out, and only valid for the current render box, so never stored
+            if let Some(dim) = source.page_size(&entry.name) {
+                remember(path, stamp, dim);
+            }
+            continue;
+        }
+        let modified = modified_secs(entry);
+        let dim = match stored.get(entry.name.as_str()) {
+            Some(p) if p.size == entry.size as i64 && p.modified == modified => Some((p.width, p.height)),
+            _ => {
+                changed = true;
+                // TIFF pages know their size without being decoded
+                cached(&path, stamp).or_else(|| source.page_size(&entry.name))
+            }
+        };
+        match dim {
+            Some((width, height)) => {
+                remember(path, stamp, (width, height));
+                pages.push(PageDimension { name: entry.name.clone(), size: entry.size as i64, modified, width, height });
+            }
+            None => {
+                names.push(entry.name.clone());
+                unmeasured.insert(entry.name.as_str(), entry);
+            }
+        }
+    }
+
+    // Everything else is measured from its header, in a single pass over the archive
+    let result = source.read_heads(&names, HEAD_LIMIT, &mut |name, head| {
+        if is_cancelled(job) {
+            return false;
+        }
+        let path = container.join(name);
+        if let (Some(entry), Ok(stamp), Some((width, height))) = (unmeasured.get(name), FileStamp::of(&path), codecs::dimensions(&path, head)) {
+            remember(path, stamp, (width, height));
+            pages.push(PageDimension { name: entry.name.clone(), size: entry.size as i64, modified: modified_secs(entry), width, height });
+        }
+        true
+    });
+    if let Err(e) = result {
+        eprintln!("Failed to measure pages of {}: {}", container.display(), e);
+    }
+    if is_cancelled(job) {
+        return;
+    }
+
+    if changed || pages.len() != stored.len() {
+        if let Some(helper) = &job.db_helper {
+            if let Err(e) = helper.save_page_dimensions(&PageDimensions { container: key, pages }) {
+                eprintln!("Failed to save page dimensions: {}", e);
+            }
+        }
+    }
+}
+
+fn modified_secs(entry: &PageEntry) -> i64 {
+    entry.modified
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+fn read_dimensions(path: &Path) -> Option<(u32, u32)> {
+    if path.is_file() {
+        let header = ImageReader::open(path).ok()
+            .and_then(|reader| reader.with_guessed_format().ok())
+            .and_then(|reader| reader.into_decoder().ok())
+            .map(codecs::displayed_size);
+        if header.is_some() {
+            return header;
+        }
+    } else if let Some(size) = page_source::page_size(path) {
+        // Rendered page (PDF, TIFF page); no need to decode it just to measure it
+        return Some(size);
+    }
+    // Archive entries, and formats the image crate cannot read (HEIF, JPEG XL, PSD)
+    let head = page_source::read_page_head(path, HEAD_LIMIT).ok()?;
+    if let Some(dim) = codecs::dimensions(path, &head) {
+        return Some(dim);
+    }
+    if head.len() < HEAD_LIMIT {
+        // That was the whole page
+        return None;
+    }
+    // Metadata larger than the head came before the image header
+    let buffer = page_source::read_page(path).ok()?;
+    codecs::dimensions(path, &buffer)
+}
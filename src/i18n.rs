@@ -43,6 +43,35 @@ fn localize_ja(key: &str) -> String {
         "Dark Mode (Requires Restart)" => "ダークモード (再起動が必要)".to_string(),
         "Loop Images (at end of list)" => "画像をループする (リストの最後で)".to_string(),
         "Single Page for First Image (Spread View)" => "最初の画像を単ページ表示 (見開き表示時)".to_string(),
+        "Prefetch Pages Ahead" => "先読みするページ数 (前方)".to_string(),
+        "Prefetch Pages Behind" => "先読みするページ数 (後方)".to_string(),
+        "Image Cache Size (MB)" => "画像キャッシュサイズ (MB)".to_string(),
+        "Zoom Step (%)" => "ズームの刻み (%)".to_string(),
+        "Minimum Zoom (%)" => "最小ズーム (%)".to_string(),
+        "Maximum Zoom (%)" => "最大ズーム (%)".to_string(),
+        "List Archive Pages Without Folders" => "アーカイブ内のフォルダを区別せずに画像を一覧表示".to_string(),
+        "Remember Archive Passwords" => "アーカイブのパスワードを記憶する".to_string(),
+        "Play Animations Automatically" => "アニメーションを自動再生する".to_string(),
+        "Color Management (Convert ICC Profiles to sRGB)" => "カラーマネジメント (ICCプロファイルをsRGBに変換)".to_string(),
+        "Downscale Large Images to Window Size" => "大きな画像をウィンドウサイズに縮小".to_string(),
+        "Rotate and Flip the Whole Folder" => "回転・反転をフォルダ全体に適用".to_string(),
+        "Password Required" => "パスワードが必要です".to_string(),
+        "Enter the password for this archive:" => "このアーカイブのパスワードを入力してください:".to_string(),
+        "Incorrect password. Please try again." => "パスワードが正しくありません。もう一度入力してください。".to_string(),
+        "Remember Password" => "パスワードを記憶する".to_string(),
+        "Remembered passwords are stored unencrypted." => "記憶したパスワードは暗号化されずに保存されます。".to_string(),
+        "This archive is password protected." => "このアーカイブはパスワードで保護されています。".to_string(),
+        "Incorrect password for this archive." => "このアーカイブのパスワードが正しくありません。".to_string(),
+        "Comic Info" => "作品情報".to_string(),
+        "Title" => "タイトル".to_string(),
+        "Series" => "シリーズ".to_string(),
+        "Volume" => "巻".to_string(),
+        "Number" => "番号".to_string(),
+        "Writer" => "作者".to_string(),
+        "Penciller" => "作画".to_string(),
+        "Publisher" => "出版社".to_string(),
+        "Year" => "年".to_string(),
+        "Genre" => "ジャンル".to_string(),
         "Input Configuration" => "入力設定".to_string(),
         "Reset to Defaults" => "デフォルトに戻す".to_string(),
         "Keyboard Shortcuts" => "キーボードショートカット".to_string(),
@@ -58,6 +87,8 @@ fn localize_ja(key: &str) -> String {
         "Date Desc" => "日付 (降順)".to_string(),
         "Size Asc" => "サイズ (昇順)".to_string(),
         "Size Desc" => "サイズ (降順)".to_string(),
+        "Series Asc" => "シリーズ (昇順)".to_string(),
+        "Series Desc" => "シリーズ (降順)".to_string(),
 
         // Mouse Inputs
         "Right Click" => "右クリック".to_string(),
@@ -79,6 +110,25 @@ fn localize_ja(key: &str) -> String {
         "Toggle Right-to-Left" => "右開き切り替え".to_string(),
         "Previous Image (Single Step)" => "前の画像 (1ページ)".to_string(),
         "Next Image (Single Step)" => "次の画像 (1ページ)".to_string(),
+        "Pause / Resume Animation" => "アニメーションの一時停止 / 再開".to_string(),
+        "Next Animation Frame" => "アニメーションの次のフレーム".to_string(),
+        "Previous Animation Frame" => "アニメーションの前のフレーム".to_string(),
+        "Speed Up Animation" => "アニメーションを速くする".to_string(),
+        "Slow Down Animation" => "アニメーションを遅くする".to_string(),
+        "Toggle Continuous Scroll" => "縦スクロール表示切り替え".to_string(),
+        "Fit Page" => "ページに合わせる".to_string(),
+        "Fit Width" => "幅に合わせる".to_string(),
+        "Fit Height" => "高さに合わせる".to_string(),
+        "Original Size (100%)" => "原寸 (100%)".to_string(),
+        "Fit Width (Up to 100%)" => "幅に合わせる (最大100%)".to_string(),
+        "Scroll or Next Image" => "スクロール / 次の画像".to_string(),
+        "Scroll or Previous Image" => "スクロール / 前の画像".to_string(),
+        "Rotate Clockwise" => "右に回転".to_string(),
+        "Rotate Counterclockwise" => "左に回転".to_string(),
+        "Rotate 180°" => "180°回転".to_string(),
+        "Flip Horizontally" => "左右反転".to_string(),
+        "Flip Vertically" => "上下反転".to_string(),
+        "Frame" => "フレーム".to_string(),
         
         "None" => "なし".to_string(),
 
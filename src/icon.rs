@@ -13,6 +13,10 @@ const VIEW_BINDING_LEFT: &[u8] = br##"<svg width="24" height="24" viewBox="0 0 2
 
 const VIEW_BINDING_RIGHT: &[u8] = br##"<svg width="24" height="24" viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path opacity="0.3" d="M2 6C2 4.89543 2.89543 4 4 4H10C11.1046 4 12 4.89543 12 6V20C12 21.1046 11.1046 22 10 22H4C2.89543 22 2 21.1046 2 20V6Z" stroke="#ffffff" stroke-width="2" stroke-linejoin="round" fill="#ffffff"/><path opacity="0.3" d="M22 6C22 4.89543 21.1046 4 20 4H14C12.8954 4 12 4.89543 12 6V20C12 21.1046 12.8954 22 14 22H20C21.1046 22 22 21.1046 22 20V6Z" stroke="#ffffff" stroke-width="2" stroke-linejoin="round" fill="#ffffff"/><path d="M17 13H7" stroke="#ffffff" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"/><path d="M10 10L7 13L10 16" stroke="#ffffff" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"/></svg>"##;
 
+const VIEW_CONTINUOUS_ON: &[u8] = br##"<svg width="24" height="24" viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M6 2H18V22H6V2Z" stroke="#ffffff" stroke-width="2" stroke-linejoin="round" fill="#ffffff" fill-opacity="0.5"/><path d="M6 9H18" stroke="#ffffff" stroke-width="2"/><path d="M6 15H18" stroke="#ffffff" stroke-width="2"/></svg>"##;
+
+const VIEW_CONTINUOUS_OFF: &[u8] = br##"<svg width="24" height="24" viewBox="0 0 24 24" fill="none" xmlns="http://www.w3.org/2000/svg"><path opacity="0.3" d="M6 2H18V22H6V2Z" stroke="#ffffff" stroke-width="2" stroke-linejoin="round" fill="#ffffff"/><path opacity="0.3" d="M6 9H18" stroke="#ffffff" stroke-width="2"/><path opacity="0.3" d="M6 15H18" stroke="#ffffff" stroke-width="2"/></svg>"##;
+
 fn load_icon(bytes: &[u8]) -> Option<gdk::Paintable> {
     let loader = PixbufLoader::new();
     if let Err(_) = loader.write(bytes) {
@@ -40,3 +44,11 @@ pub fn binding_left() -> Option<gdk::Paintable> {
 pub fn binding_right() -> Option<gdk::Paintable> {
     load_icon(VIEW_BINDING_RIGHT)
 }
+
+pub fn continuous_on() -> Option<gdk::Paintable> {
+    load_icon(VIEW_CONTINUOUS_ON)
+}
+
+pub fn continuous_off() -> Option<gdk::Paintable> {
+    load_icon(VIEW_CONTINUOUS_OFF)
+}
@@ -18,6 +18,24 @@ pub enum Action {
     ToggleRTL,
     PrevPageSingle,
     NextPageSingle,
+    ToggleAnimation,
+    NextFrame,
+    PrevFrame,
+    AnimationFaster,
+    AnimationSlower,
+    ToggleContinuous,
+    FitPage,
+    FitWidth,
+    FitHeight,
+    FitOriginal,
+    FitWidthCapped,
+    SmartNextPage,
+    SmartPrevPage,
+    RotateClockwise,
+    RotateCounterClockwise,
+    Rotate180,
+    FlipHorizontal,
+    FlipVertical,
 }
 
 impl Action {
@@ -35,6 +53,24 @@ impl Action {
             Action::ToggleRTL => "Toggle Right-to-Left",
             Action::PrevPageSingle => "Previous Image (Single Step)",
             Action::NextPageSingle => "Next Image (Single Step)",
+            Action::ToggleAnimation => "Pause / Resume Animation",
+            Action::NextFrame => "Next Animation Frame",
+            Action::PrevFrame => "Previous Animation Frame",
+            Action::AnimationFaster => "Speed Up Animation",
+            Action::AnimationSlower => "Slow Down Animation",
+            Action::ToggleContinuous => "Toggle Continuous Scroll",
+            Action::FitPage => "Fit Page",
+            Action::FitWidth => "Fit Width",
+            Action::FitHeight => "Fit Height",
+            Action::FitOriginal => "Original Size (100%)",
+            Action::FitWidthCapped => "Fit Width (Up to 100%)",
+            Action::SmartNextPage => "Scroll or Next Image",
+            Action::SmartPrevPage => "Scroll or Previous Image",
+            Action::RotateClockwise => "Rotate Clockwise",
+            Action::RotateCounterClockwise => "Rotate Counterclockwise",
+            Action::Rotate180 => "Rotate 180°",
+            Action::FlipHorizontal => "Flip Horizontally",
+            Action::FlipVertical => "Flip Vertically",
         };
         localize(key, lang)
     }
@@ -52,6 +88,24 @@ impl Action {
             Action::ToggleRTL,
             Action::PrevPageSingle,
             Action::NextPageSingle,
+            Action::ToggleAnimation,
+            Action::NextFrame,
+            Action::PrevFrame,
+            Action::AnimationFaster,
+            Action::AnimationSlower,
+            Action::ToggleContinuous,
+            Action::FitPage,
+            Action::FitWidth,
+            Action::FitHeight,
+            Action::FitOriginal,
+            Action::FitWidthCapped,
+            Action::SmartNextPage,
+            Action::SmartPrevPage,
+            Action::RotateClockwise,
+            Action::RotateCounterClockwise,
+            Action::Rotate180,
+            Action::FlipHorizontal,
+            Action::FlipVertical,
         ]
     }
 }
@@ -101,10 +155,25 @@ impl InputSpec {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "SavedInputMap")]
 pub struct InputMap {
     pub map: HashMap<Action, Vec<InputSpec>>,
 }
 
+/// An `InputMap` as stored, possibly from before some actions existed.
+#[derive(Deserialize)]
+struct SavedInputMap {
+    map: HashMap<Action, Vec<InputSpec>>,
+}
+
+impl From<SavedInputMap> for InputMap {
+    fn from(saved: SavedInputMap) -> Self {
+        let mut input_map = InputMap { map: saved.map };
+        input_map.add_missing_defaults();
+        input_map
+    }
+}
+
 impl Default for InputMap {
     fn default() -> Self {
         let mut map = HashMap::new();
@@ -174,11 +243,94 @@ impl Default for InputMap {
              InputSpec::Keyboard { keyval: gdk::Key::Right.into_glib(), modifiers: gdk::ModifierType::SHIFT_MASK.bits() }
         ]);
 
+        // ToggleAnimation: Space
+        map.insert(Action::ToggleAnimation, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::space.into_glib(), modifiers: 0 }
+        ]);
+
+        // NextFrame: . (period), PrevFrame: , (comma)
+        map.insert(Action::NextFrame, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::period.into_glib(), modifiers: 0 }
+        ]);
+        map.insert(Action::PrevFrame, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::comma.into_glib(), modifiers: 0 }
+        ]);
+
+        // AnimationFaster: ], AnimationSlower: [
+        map.insert(Action::AnimationFaster, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::bracketright.into_glib(), modifiers: 0 }
+        ]);
+        map.insert(Action::AnimationSlower, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::bracketleft.into_glib(), modifiers: 0 }
+        ]);
+
+        // ToggleContinuous: W (webtoon)
+        map.insert(Action::ToggleContinuous, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::w.into_glib(), modifiers: 0 }
+        ]);
+
+        // Fit modes: 1 (page), 2 (width), 3 (height), 4 (100%), 5 (width up to 100%)
+        map.insert(Action::FitPage, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::_1.into_glib(), modifiers: 0 }
+        ]);
+        map.insert(Action::FitWidth, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::_2.into_glib(), modifiers: 0 }
+        ]);
+        map.insert(Action::FitHeight, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::_3.into_glib(), modifiers: 0 }
+        ]);
+        map.insert(Action::FitOriginal, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::_4.into_glib(), modifiers: 0 }
+        ]);
+        map.insert(Action::FitWidthCapped, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::_5.into_glib(), modifiers: 0 }
+        ]);
+
+        // SmartNextPage: Page Down, SmartPrevPage: Page Up
+        map.insert(Action::SmartNextPage, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::Page_Down.into_glib(), modifiers: 0 }
+        ]);
+        map.insert(Action::SmartPrevPage, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::Page_Up.into_glib(), modifiers: 0 }
+        ]);
+
+        // RotateClockwise: R, RotateCounterClockwise: Shift + R, FlipHorizontal: H, FlipVertical: V
+        map.insert(Action::RotateClockwise, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::r.into_glib(), modifiers: 0 }
+        ]);
+        map.insert(Action::RotateCounterClockwise, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::r.into_glib(), modifiers: gdk::ModifierType::SHIFT_MASK.bits() }
+        ]);
+        map.insert(Action::FlipHorizontal, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::h.into_glib(), modifiers: 0 }
+        ]);
+        map.insert(Action::FlipVertical, vec![
+            InputSpec::Keyboard { keyval: gdk::Key::v.into_glib(), modifiers: 0 }
+        ]);
+
         Self { map }
     }
 }
 
 impl InputMap {
+    /// Give actions added since the map was saved their default bindings, skipping
+    /// inputs the user already assigned elsewhere. Actions the user cleared keep
+    /// their (empty) entry and are left alone.
+    pub fn add_missing_defaults(&mut self) {
+        let defaults = InputMap::default();
+        for action in Action::variants() {
+            if self.map.contains_key(action) {
+                continue;
+            }
+            let specs = defaults.map.get(action).cloned().unwrap_or_default()
+                .into_iter()
+                .filter(|spec| !self.map.values().any(|specs| specs.contains(spec)))
+                .collect();
+            self.map.insert(*action, specs);
+        }
+    }
+
+
     pub fn get_action_for_key(&self, key: gdk::Key, modifiers: gdk::ModifierType) -> Option<Action> {
         for (action, specs) in &self.map {
             for spec in specs {
@@ -212,3 +364,36 @@ impl InputMap {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polodb_core::bson;
+
+    fn key(keyval: gdk::Key, modifiers: u32) -> InputSpec {
+        InputSpec::Keyboard { keyval: keyval.into_glib(), modifiers }
+    }
+
+    #[test]
+    fn saved_map_gains_bindings_for_new_actions() {
+        // Saved before rotation and fit modes existed, with R taken for Next Image and zoom in cleared
+        let saved = InputMap {
+            map: HashMap::from([
+                (Action::NextPage, vec![key(gdk::Key::r, 0)]),
+                (Action::ZoomIn, Vec::new()),
+            ]),
+        };
+        let document = bson::doc! { "map": bson::to_bson(&saved.map).unwrap() };
+        let loaded: InputMap = bson::from_document(document).unwrap();
+
+        assert_eq!(loaded.map[&Action::NextPage], vec![key(gdk::Key::r, 0)]);
+        assert!(loaded.map[&Action::ZoomIn].is_empty());
+        assert_eq!(loaded.map[&Action::FlipHorizontal], vec![key(gdk::Key::h, 0)]);
+        assert_eq!(loaded.map[&Action::FitPage], vec![key(gdk::Key::_1, 0)]);
+        assert_eq!(loaded.map[&Action::SmartNextPage], vec![key(gdk::Key::Page_Down, 0)]);
+        assert_eq!(loaded.map[&Action::ToggleContinuous], vec![key(gdk::Key::w, 0)]);
+        // Its default key already belongs to another action
+        assert!(loaded.map[&Action::RotateClockwise].is_empty());
+        assert!(Action::variants().iter().all(|action| loaded.map.contains_key(action)));
+    }
+}
@@ -1,10 +1,17 @@
 use relm4::prelude::*;
 
+mod anim_stream;
 mod app;
+mod codecs;
+mod comic_info;
 mod components;
 mod database;
+mod dimension_cache;
 mod i18n;
 mod icon;
+mod oriented_paintable;
+mod page_source;
+mod texture_cache;
 mod utils;
 
 use app::AppModel;
@@ -0,0 +1,97 @@
+use gtk4::gdk;
+use gtk4::glib;
+use gtk4::graphene;
+use gtk4::prelude::*;
+use gtk4::subclass::prelude::*;
+
+use crate::database::PageTransform;
+
+/// Show `texture` rotated and mirrored. Untransformed pages are shown as they are.
+pub fn orient(texture: gdk::Texture, transform: PageTransform) -> gdk::Paintable {
+    if transform.is_identity() {
+        texture.upcast()
+    } else {
+        OrientedPaintable::new(&texture, transform).upcast()
+    }
+}
+
+mod imp {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    pub struct OrientedPaintable {
+        pub inner: RefCell<Option<gdk::Texture>>,
+        pub transform: Cell<PageTransform>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for OrientedPaintable {
+        const NAME: &'static str = "CiviewerOrientedPaintable";
+        type Type = super::OrientedPaintable;
+        type Interfaces = (gdk::Paintable,);
+    }
+
+    impl ObjectImpl for OrientedPaintable {}
+
+    impl OrientedPaintable {
+        /// Size of the page before the transform.
+        fn source_size(&self) -> (i32, i32) {
+            self.inner.borrow().as_ref().map_or((0, 0), |t| (t.width(), t.height()))
+        }
+    }
+
+    impl PaintableImpl for OrientedPaintable {
+        fn flags(&self) -> gdk::PaintableFlags {
+            gdk::PaintableFlags::CONTENTS | gdk::PaintableFlags::SIZE
+        }
+
+        fn intrinsic_width(&self) -> i32 {
+            let (width, height) = self.source_size();
+            if self.transform.get().swaps_axes() { height } else { width }
+        }
+
+        fn intrinsic_height(&self) -> i32 {
+            let (width, height) = self.source_size();
+            if self.transform.get().swaps_axes() { width } else { height }
+        }
+
+        fn intrinsic_aspect_ratio(&self) -> f64 {
+            let (width, height) = (self.intrinsic_width(), self.intrinsic_height());
+            if width > 0 && height > 0 { width as f64 / height as f64 } else { 0.0 }
+        }
+
+        fn snapshot(&self, snapshot: &gdk::Snapshot, width: f64, height: f64) {
+            let Some(texture) = self.inner.borrow().clone() else { return; };
+            let Some(snapshot) = snapshot.downcast_ref::<gtk4::Snapshot>() else { return; };
+            let transform = self.transform.get();
+            // The page is drawn at its own orientation, so a turned page gets the sides swapped
+            let (draw_w, draw_h) = if transform.swaps_axes() { (height, width) } else { (width, height) };
+
+            snapshot.save();
+            snapshot.translate(&graphene::Point::new(width as f32 / 2.0, height as f32 / 2.0));
+            snapshot.rotate(90.0 * (transform.quarter_turns % 4) as f32);
+            if transform.mirrored {
+                snapshot.scale(-1.0, 1.0);
+            }
+            snapshot.translate(&graphene::Point::new(-draw_w as f32 / 2.0, -draw_h as f32 / 2.0));
+            texture.snapshot(snapshot, draw_w, draw_h);
+            snapshot.restore();
+        }
+    }
+}
+
+glib::wrapper! {
+    /// A page texture turned by quarter turns and optionally mirrored. Reports the
+    /// turned size, so layout and fit zoom treat it like any other page.
+    pub struct OrientedPaintable(ObjectSubclass<imp::OrientedPaintable>) @implements gdk::Paintable;
+}
+
+impl OrientedPaintable {
+    pub fn new(texture: &gdk::Texture, transform: PageTransform) -> Self {
+        let paintable: Self = glib::Object::new();
+        paintable.imp().inner.replace(Some(texture.clone()));
+        paintable.imp().transform.set(transform);
+        paintable
+    }
+}
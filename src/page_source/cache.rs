@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use super::PageSource;
+
+/// Upper bound on archives kept open at once. Small on purpose: each entry may hold
+/// a file descriptor and a parsed index, and readers rarely hop between more books.
+const MAX_OPEN_SOURCES: usize = 8;
+
+/// Size and modification time of a file, to tell when what was learned about it is stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileStamp {
+    pub fn of(path: &Path) -> Result<Self> {
+        // Nested archives change whenever the archive on disk that holds them does
+        let on_disk = path.ancestors().find(|p| p.is_file()).unwrap_or(path);
+        let meta = std::fs::metadata(on_disk)?;
+        Ok(Self { modified: meta.modified().ok(), len: meta.len() })
+    }
+}
+
+type SharedSource = Arc<Mutex<Box<dyn PageSource>>>;
+
+struct CachedSource {
+    path: PathBuf,
+    stamp: FileStamp,
+    source: SharedSource,
+}
+
+// Most recently used first. Shared by the sidebar (main thread) and the image loader threads.
+static OPEN_SOURCES: Lazy<Mutex<VecDeque<CachedSource>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+/// Run `f` against the container at `path`, reusing an already opened archive when the
+/// file on disk has not changed since it was opened.
+pub fn with_source<R>(path: &Path, f: impl FnOnce(&mut dyn PageSource) -> Result<R>) -> Result<R> {
+    // Directories hold no state worth keeping
+    if path.is_dir() {
+        let mut source = super::open(path)?;
+        return f(source.as_mut());
+    }
+
+    let stamp = FileStamp::of(path)?;
+    let source = match lookup(path, stamp) {
+        Some(source) => source,
+        None => {
+            // Open outside the cache lock so a slow archive doesn't stall other readers
+            let source: SharedSource = Arc::new(Mutex::new(super::open(path)?));
+            insert(path, stamp, source.clone());
+            source
+        }
+    };
+
+    let result = {
+        let mut guard = source.lock().unwrap_or_else(|e| e.into_inner());
+        f(guard.as_mut())
+    };
+    if let Err(e) = &result {
+        // The handle may be in a bad state (truncated file, I/O error); reopen next time.
+        // A missing or wrong password says nothing about the handle itself.
+        if e.downcast_ref::<super::PasswordError>().is_none() {
+            invalidate(path);
+        }
+    }
+    result
+}
+
+/// Drop any cached handle for `path`.
+fn invalidate(path: &Path) {
+    let mut cache = OPEN_SOURCES.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|c| c.path != path);
+}
+
+fn lookup(path: &Path, stamp: FileStamp) -> Option<SharedSource> {
+    let mut cache = OPEN_SOURCES.lock().unwrap_or_else(|e| e.into_inner());
+    let idx = cache.iter().position(|c| c.path == path)?;
+    let cached = cache.remove(idx)?;
+    if cached.stamp != stamp {
+        // Modified on disk since it was opened
+        return None;
+    }
+    let source = cached.source.clone();
+    cache.push_front(cached);
+    Some(source)
+}
+
+fn insert(path: &Path, stamp: FileStamp, source: SharedSource) {
+    let mut cache = OPEN_SOURCES.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|c| c.path != path);
+    cache.push_front(CachedSource { path: path.to_path_buf(), stamp, source });
+    cache.truncate(MAX_OPEN_SOURCES);
+}
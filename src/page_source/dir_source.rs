@@ -0,0 +1,45 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use super::{PageEntry, PageSource};
+
+/// Plain directory on disk. Only direct children are listed.
+pub struct DirSource {
+    root: PathBuf,
+}
+
+impl DirSource {
+    pub fn new(root: &Path) -> Self {
+        Self { root: root.to_path_buf() }
+    }
+}
+
+impl PageSource for DirSource {
+    fn entries(&mut self) -> Result<Vec<PageEntry>> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&self.root)?.flatten() {
+            let Ok(meta) = entry.metadata() else { continue; };
+            if !meta.is_file() {
+                continue;
+            }
+            if let Some(name) = entry.file_name().to_str() {
+                entries.push(PageEntry {
+                    name: name.to_string(),
+                    size: meta.len(),
+                    modified: meta.modified().ok(),
+                });
+            }
+        }
+        Ok(entries)
+    }
+
+    fn read(&mut self, name: &str) -> Result<Vec<u8>> {
+        Ok(std::fs::read(self.root.join(name))?)
+    }
+
+    fn read_head(&mut self, name: &str, limit: usize) -> Result<Vec<u8>> {
+        let mut head = Vec::new();
+        std::fs::File::open(self.root.join(name))?.take(limit as u64).read_to_end(&mut head)?;
+        Ok(head)
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use encoding_rs::{GBK, SHIFT_JIS};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+/// Character set used for archive entry names that are not flagged as UTF-8.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NameEncoding {
+    #[default]
+    Auto,
+    Utf8,
+    ShiftJis,
+    Cp437,
+    Gbk,
+}
+
+impl NameEncoding {
+    pub fn variants() -> &'static [NameEncoding] {
+        &[
+            NameEncoding::Auto,
+            NameEncoding::Utf8,
+            NameEncoding::ShiftJis,
+            NameEncoding::Cp437,
+            NameEncoding::Gbk,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NameEncoding::Auto => "Auto",
+            NameEncoding::Utf8 => "UTF-8",
+            NameEncoding::ShiftJis => "Shift-JIS",
+            NameEncoding::Cp437 => "CP437",
+            NameEncoding::Gbk => "GBK",
+        }
+    }
+}
+
+// Per-archive overrides chosen by the user; archives not listed use `Auto`.
+static OVERRIDES: Lazy<Mutex<HashMap<PathBuf, NameEncoding>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn set_name_encoding(archive: &Path, encoding: NameEncoding) {
+    let mut overrides = OVERRIDES.lock().unwrap_or_else(|e| e.into_inner());
+    if encoding == NameEncoding::Auto {
+        overrides.remove(archive);
+    } else {
+        overrides.insert(archive.to_path_buf(), encoding);
+    }
+}
+
+pub fn name_encoding(archive: &Path) -> NameEncoding {
+    OVERRIDES.lock().unwrap_or_else(|e| e.into_inner()).get(archive).copied().unwrap_or_default()
+}
+
+/// Decode raw entry names, each paired with whether the archive flags it as UTF-8.
+/// Flagged names are always UTF-8. With `Auto`, other names that are valid UTF-8 are kept
+/// as such and the rest are decoded with a single legacy code page guessed from all of them.
+pub fn decode_names(raw_names: &[(&[u8], bool)], encoding: NameEncoding) -> Vec<String> {
+    let legacy = match encoding {
+        NameEncoding::Auto => {
+            let unflagged: Vec<&[u8]> = raw_names.iter().filter(|(_, utf8)| !utf8).map(|(raw, _)| *raw).collect();
+            detect_legacy(&unflagged)
+        }
+        other => other,
+    };
+    raw_names.iter().map(|&(raw, utf8)| {
+        if utf8 {
+            return String::from_utf8_lossy(raw).into_owned();
+        }
+        match std::str::from_utf8(raw) {
+            Ok(s) if encoding == NameEncoding::Auto || encoding == NameEncoding::Utf8 => s.to_string(),
+            _ => decode(raw, legacy),
+        }
+    }).collect()
+}
+
+fn decode(raw: &[u8], encoding: NameEncoding) -> String {
+    match encoding {
+        NameEncoding::ShiftJis => SHIFT_JIS.decode_without_bom_handling(raw).0.into_owned(),
+        NameEncoding::Gbk => GBK.decode_without_bom_handling(raw).0.into_owned(),
+        NameEncoding::Cp437 => decode_cp437(raw),
+        NameEncoding::Auto | NameEncoding::Utf8 => String::from_utf8_lossy(raw).into_owned(),
+    }
+}
+
+// Shift-JIS and GBK accept many of the same byte sequences, so when both decode cleanly
+// the one whose reading is made of more common characters wins. Ties go to Shift-JIS,
+// as Japanese comics are the common case here.
+fn detect_legacy(raw_names: &[&[u8]]) -> NameEncoding {
+    let non_utf8: Vec<&[u8]> = raw_names.iter().copied().filter(|raw| std::str::from_utf8(raw).is_err()).collect();
+    if non_utf8.is_empty() {
+        return NameEncoding::Utf8;
+    }
+
+    let sjis_ok = non_utf8.iter().all(|raw| SHIFT_JIS.decode_without_bom_handling_and_without_replacement(raw).is_some());
+    let gbk_ok = non_utf8.iter().all(|raw| GBK.decode_without_bom_handling_and_without_replacement(raw).is_some());
+    match (sjis_ok, gbk_ok) {
+        (true, true) => {
+            let sjis: usize = non_utf8.iter().map(|raw| common_japanese(raw)).sum();
+            let gbk: usize = non_utf8.iter().map(|raw| common_chinese(raw)).sum();
+            if gbk > sjis { NameEncoding::Gbk } else { NameEncoding::ShiftJis }
+        }
+        (true, false) => NameEncoding::ShiftJis,
+        (false, true) => NameEncoding::Gbk,
+        (false, false) => NameEncoding::Cp437,
+    }
+}
+
+/// Characters of `raw` read as Shift-JIS that are full-width kana or JIS X 0208
+/// level-1 kanji, which is what Japanese names are mostly written with.
+fn common_japanese(raw: &[u8]) -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i < raw.len() {
+        let lead = raw[i];
+        let (Some(&trail), 0x81..=0x9F | 0xE0..=0xFC) = (raw.get(i + 1), lead) else {
+            i += 1;
+            continue;
+        };
+        let common = match lead {
+            0x82 => (0x9F..=0xF1).contains(&trail), // hiragana
+            0x83 => (0x40..=0x96).contains(&trail), // katakana
+            0x88 => trail >= 0x9F,
+            0x89..=0x97 => true,
+            0x98 => trail <= 0x72,
+            _ => false,
+        };
+        count += common as usize;
+        i += 2;
+    }
+    count
+}
+
+/// Characters of `raw` read as GBK that are GB2312 level-1 hanzi, the common characters
+/// of simplified Chinese. Shift-JIS kanji land in GBK's extension rows instead.
+fn common_chinese(raw: &[u8]) -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i < raw.len() {
+        let lead = raw[i];
+        let (Some(&trail), 0x81..=0xFE) = (raw.get(i + 1), lead) else {
+            i += 1;
+            continue;
+        };
+        count += ((0xB0..=0xD7).contains(&lead) && (0xA1..=0xFE).contains(&trail)) as usize;
+        i += 2;
+    }
+    count
+}
+
+// Upper half of IBM code page 437, the ZIP specification's default for legacy names.
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{A0}',
+];
+
+fn decode_cp437(raw: &[u8]) -> String {
+    raw.iter().map(|&b| if b < 0x80 { b as char } else { CP437_HIGH[(b - 0x80) as usize] }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_auto(names: &[&str], encoding: &'static encoding_rs::Encoding) -> Vec<String> {
+        let raw: Vec<Vec<u8>> = names.iter().map(|n| encoding.encode(n).0.into_owned()).collect();
+        let raw: Vec<(&[u8], bool)> = raw.iter().map(|r| (r.as_slice(), false)).collect();
+        decode_names(&raw, NameEncoding::Auto)
+    }
+
+    #[test]
+    fn detects_shift_jis_with_kana() {
+        let names = ["ワンピース/第1話.jpg", "ワンピース/表紙.png"];
+        assert_eq!(decode_auto(&names, SHIFT_JIS), names);
+    }
+
+    #[test]
+    fn detects_shift_jis_with_only_kanji() {
+        let names = ["漫画全集/第一巻.jpg", "漫画全集/表紙.jpg"];
+        assert_eq!(decode_auto(&names, SHIFT_JIS), names);
+    }
+
+    #[test]
+    fn detects_gbk() {
+        let names = ["海贼王/第一卷.jpg", "海贼王/封面.jpg"];
+        assert_eq!(decode_auto(&names, GBK), names);
+    }
+
+    #[test]
+    fn keeps_names_flagged_as_utf8() {
+        let sjis = SHIFT_JIS.encode("表紙.jpg").0.into_owned();
+        let raw: [(&[u8], bool); 2] = [("第一巻.jpg".as_bytes(), true), (&sjis, false)];
+        assert_eq!(decode_names(&raw, NameEncoding::ShiftJis), ["第一巻.jpg", "表紙.jpg"]);
+        assert_eq!(decode_names(&raw, NameEncoding::Auto), ["第一巻.jpg", "表紙.jpg"]);
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::path::Path;
+use anyhow::{anyhow, Result};
+use crate::utils::is_supported_image;
+use super::{PageEntry, PageSource, ZipSource};
+
+const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
+
+/// An EPUB whose pages are the images of its spine, listed in reading order as
+/// `001.jpg`, `002.png`, ... so that name sorting keeps the spine order.
+pub struct EpubSource {
+    zip: ZipSource,
+    // Page name -> entry inside the ZIP, in spine order
+    pages: Vec<(String, String)>,
+    // Declared page progression; None when the spine leaves it to the reader
+    right_to_left: Option<bool>,
+}
+
+impl EpubSource {
+    pub fn open(file: &Path, path: &Path) -> Result<Self> {
+        let mut zip = ZipSource::open(file, path)?;
+        let (spine, right_to_left) = read_spine(&mut zip)?;
+        let width = spine.len().to_string().len();
+        let pages = spine.into_iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let ext = Path::new(&entry).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+                (format!("{:0width$}.{}", i + 1, ext, width = width), entry)
+            })
+            .collect();
+        Ok(Self { zip, pages, right_to_left })
+    }
+
+    fn entry_of(&self, name: &str) -> Result<&str> {
+        self.pages.iter()
+            .find(|(page, _)| page == name)
+            .map(|(_, entry)| entry.as_str())
+            .ok_or_else(|| anyhow!("Page not found in EPUB: {}", name))
+    }
+}
+
+impl PageSource for EpubSource {
+    fn entries(&mut self) -> Result<Vec<PageEntry>> {
+        let stored: HashMap<String, PageEntry> = self.zip.entries()?.into_iter().map(|e| (e.name.clone(), e)).collect();
+        let entries = self.pages.iter()
+            .filter_map(|(page, entry)| {
+                let stored = stored.get(entry)?;
+                Some(PageEntry { name: page.clone(), size: stored.size, modified: stored.modified })
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    fn read(&mut self, name: &str) -> Result<Vec<u8>> {
+        let entry = self.entry_of(name)?.to_string();
+        self.zip.read(&entry)
+    }
+
+    fn read_head(&mut self, name: &str, limit: usize) -> Result<Vec<u8>> {
+        let entry = self.entry_of(name)?.to_string();
+        self.zip.read_head(&entry, limit)
+    }
+
+    fn is_encrypted(&mut self) -> bool {
+        self.zip.is_encrypted()
+    }
+
+    fn right_to_left(&mut self) -> Option<bool> {
+        self.right_to_left
+    }
+}
+
+/// Images of the spine in reading order, and the direction the spine declares, if any.
+fn read_spine(zip: &mut ZipSource) -> Result<(Vec<String>, Option<bool>)> {
+    let container = String::from_utf8(zip.read("META-INF/container.xml")?)?;
+    let container = roxmltree::Document::parse(&container)?;
+    let opf_path = container.descendants()
+        .find(|n| n.has_tag_name("rootfile"))
+        .and_then(|n| n.attribute("full-path"))
+        .ok_or_else(|| anyhow!("EPUB has no package document"))?
+        .to_string();
+
+    let opf = String::from_utf8(zip.read(&opf_path)?)?;
+    let opf = roxmltree::Document::parse(&opf)?;
+    let manifest: HashMap<&str, (&str, &str)> = opf.descendants()
+        .filter(|n| n.has_tag_name("item"))
+        .filter_map(|n| Some((n.attribute("id")?, (n.attribute("href")?, n.attribute("media-type").unwrap_or("")))))
+        .collect();
+    let spine = opf.descendants()
+        .find(|n| n.has_tag_name("spine"))
+        .ok_or_else(|| anyhow!("EPUB package has no spine"))?;
+    let right_to_left = progression_direction(spine.attribute("page-progression-direction"));
+
+    let mut images = Vec::new();
+    for idref in spine.children().filter(|n| n.has_tag_name("itemref")).filter_map(|n| n.attribute("idref")) {
+        let Some(&(href, media_type)) = manifest.get(idref) else { continue; };
+        let entry = resolve_href(&opf_path, href);
+        if media_type.starts_with("image/") || is_supported_image(Path::new(&entry)) {
+            images.push(entry);
+        } else if let Some(image) = page_image(zip, &entry) {
+            // Fixed-layout pages are XHTML documents wrapping a single image
+            images.push(image);
+        }
+    }
+    Ok((images, right_to_left))
+}
+
+/// Whether a `page-progression-direction` is right to left. `default`, or no attribute,
+/// leaves the direction to the reader.
+fn progression_direction(attribute: Option<&str>) -> Option<bool> {
+    match attribute {
+        Some("rtl") => Some(true),
+        Some("ltr") => Some(false),
+        _ => None,
+    }
+}
+
+/// The image shown by an XHTML page, from an `<img>` or an SVG `<image>`.
+fn page_image(zip: &mut ZipSource, page: &str) -> Option<String> {
+    let xhtml = String::from_utf8(zip.read(page).ok()?).ok()?;
+    let options = roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() };
+    let doc = roxmltree::Document::parse_with_options(&xhtml, options).ok()?;
+    let href = doc.descendants().find_map(|n| match n.tag_name().name() {
+        "img" => n.attribute("src"),
+        "image" => n.attribute((XLINK_NS, "href")).or_else(|| n.attribute("href")),
+        _ => None,
+    })?;
+    Some(resolve_href(page, href))
+}
+
+/// Resolve `href` relative to the document at `base`, both inside the EPUB.
+fn resolve_href(base: &str, href: &str) -> String {
+    let href = percent_decode(href.split(['#', '?']).next().unwrap_or(href));
+    let mut parts: Vec<&str> = base.split('/').collect();
+    parts.pop();
+    for part in href.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => { parts.pop(); }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_declared_direction_only() {
+        assert_eq!(progression_direction(Some("rtl")), Some(true));
+        assert_eq!(progression_direction(Some("ltr")), Some(false));
+        assert_eq!(progression_direction(Some("default")), None);
+        assert_eq!(progression_direction(None), None);
+    }
+
+    #[test]
+    fn resolves_hrefs_against_the_document() {
+        assert_eq!(resolve_href("OEBPS/content.opf", "images/001.jpg"), "OEBPS/images/001.jpg");
+        assert_eq!(resolve_href("OEBPS/text/p1.xhtml", "../images/001.jpg"), "OEBPS/images/001.jpg");
+        assert_eq!(resolve_href("OEBPS/text/p1.xhtml", "./p2.xhtml#page"), "OEBPS/text/p2.xhtml");
+        assert_eq!(resolve_href("content.opf", "my%20image.png"), "my image.png");
+    }
+
+    #[test]
+    fn percent_decodes_utf8_and_keeps_stray_percents() {
+        assert_eq!(percent_decode("%E8%A1%A8%E7%B4%99.jpg"), "表紙.jpg");
+        assert_eq!(percent_decode("100%.png"), "100%.png");
+        assert_eq!(percent_decode("a%2"), "a%2");
+    }
+}
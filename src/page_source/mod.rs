@@ -0,0 +1,433 @@
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use anyhow::{anyhow, Result};
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::{DynamicImage, ImageFormat};
+use crate::codecs::{self, Format};
+
+mod cache;
+mod dir_source;
+mod encoding;
+mod epub_source;
+mod nested_source;
+mod password;
+mod pdf_source;
+mod rar_source;
+mod sevenz_source;
+mod tar_source;
+mod tiff_source;
+mod zip_source;
+
+pub use cache::{with_source, FileStamp};
+pub use dir_source::DirSource;
+pub use encoding::{name_encoding, set_name_encoding, NameEncoding};
+pub use epub_source::EpubSource;
+pub use nested_source::NestedSource;
+pub use password::{has_password, password_for, set_password, PasswordError};
+pub use pdf_source::{render_box as pdf_render_box, set_render_box as set_pdf_render_box, PdfSource};
+pub use rar_source::RarSource;
+pub use sevenz_source::SevenZipSource;
+pub use tar_source::TarSource;
+pub use tiff_source::{is_multipage_tiff, TiffSource};
+pub use zip_source::ZipSource;
+
+/// A file inside a page container.
+#[derive(Debug, Clone)]
+pub struct PageEntry {
+    /// '/'-separated name relative to the container root.
+    pub name: String,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// A container that holds pages: a plain directory, an archive or a document.
+pub trait PageSource: Send {
+    /// All file entries of the container, in storage order.
+    fn entries(&mut self) -> Result<Vec<PageEntry>>;
+    /// Read a single entry fully into memory.
+    fn read(&mut self, name: &str) -> Result<Vec<u8>>;
+    /// Write a single entry to `out`, streaming it where the format allows.
+    fn copy_to(&mut self, name: &str, out: &mut dyn Write) -> Result<()> {
+        out.write_all(&self.read(name)?)?;
+        Ok(())
+    }
+    /// Read at most the first `limit` bytes of an entry, enough to parse its header.
+    fn read_head(&mut self, name: &str, limit: usize) -> Result<Vec<u8>> {
+        let mut data = self.read(name)?;
+        data.truncate(limit);
+        Ok(data)
+    }
+    /// Read the heads of several entries, passing each to `visit` until it returns false.
+    /// Formats that can only be read front to back override this to do it in one pass.
+    /// Entries that cannot be read are left out.
+    fn read_heads(&mut self, names: &[String], limit: usize, visit: &mut dyn FnMut(&str, &[u8]) -> bool) -> Result<()> {
+        for name in names {
+            let Ok(head) = self.read_head(name, limit) else { continue; };
+            if !visit(name, &head) {
+                break;
+            }
+        }
+        Ok(())
+    }
+    /// Whether reading pages needs a password. Only ZIP encryption is supported.
+    fn is_encrypted(&mut self) -> bool {
+        false
+    }
+    /// Pixel size of a page that is rendered rather than stored, known without rendering it.
+    fn page_size(&mut self, _name: &str) -> Option<(u32, u32)> {
+        None
+    }
+    /// Reading direction the container declares for itself (EPUB page progression).
+    fn right_to_left(&mut self) -> Option<bool> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Rar,
+    SevenZip,
+    Tar,
+    TarGz,
+    Pdf,
+    Epub,
+}
+
+impl ArchiveKind {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name().and_then(|s| s.to_str())?.to_lowercase();
+        // Compound extensions have to be checked before the plain ones
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            return Some(ArchiveKind::TarGz);
+        }
+        let ext = path.extension().and_then(|s| s.to_str())?.to_lowercase();
+        match ext.as_str() {
+            "zip" | "cbz" => Some(ArchiveKind::Zip),
+            "rar" | "cbr" => Some(ArchiveKind::Rar),
+            "7z" | "cb7" => Some(ArchiveKind::SevenZip),
+            "tar" | "cbt" => Some(ArchiveKind::Tar),
+            "pdf" => Some(ArchiveKind::Pdf),
+            "epub" => Some(ArchiveKind::Epub),
+            _ => None,
+        }
+    }
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    ArchiveKind::from_path(path).is_some()
+}
+
+/// True for files that are opened as page containers rather than shown as images:
+/// archives, documents and multi-page TIFFs.
+pub fn is_container_file(path: &Path) -> bool {
+    is_archive(path) || is_multipage_tiff(path)
+}
+
+/// True when `path` is an encrypted archive and no password has been supplied yet.
+pub fn needs_password(path: &Path) -> bool {
+    if path.is_dir() || has_password(path) {
+        return false;
+    }
+    match with_source(path, |source| Ok(source.is_encrypted())) {
+        Ok(encrypted) => encrypted,
+        // PDFs cannot even be opened without their password
+        Err(e) => e.downcast_ref::<PasswordError>().is_some(),
+    }
+}
+
+/// True for pages of a PDF, which are rendered to fit [`pdf_render_box`].
+pub fn is_pdf_page(path: &Path) -> bool {
+    path.ancestors().skip(1).any(|p| ArchiveKind::from_path(p) == Some(ArchiveKind::Pdf))
+}
+
+/// Open the container at `path`. Directories are opened as-is, files by archive type,
+/// and paths that only exist inside another archive as nested archives.
+pub fn open(path: &Path) -> Result<Box<dyn PageSource>> {
+    if path.is_dir() {
+        return Ok(Box::new(DirSource::new(path)));
+    }
+    if !path.is_file() {
+        return Ok(Box::new(NestedSource::open(path)?));
+    }
+    open_archive(path, path)
+}
+
+/// Open the archive stored in `file`. `path` is the name it is known by, which decides the
+/// archive type and keys passwords and name encodings; it differs from `file` for nested archives.
+fn open_archive(file: &Path, path: &Path) -> Result<Box<dyn PageSource>> {
+    match ArchiveKind::from_path(path) {
+        Some(ArchiveKind::Zip) => Ok(Box::new(ZipSource::open(file, path)?)),
+        Some(ArchiveKind::Rar) => Ok(Box::new(RarSource::new(file))),
+        Some(ArchiveKind::SevenZip) => Ok(Box::new(SevenZipSource::open(file)?)),
+        Some(ArchiveKind::Tar) => Ok(Box::new(TarSource::new(file, false))),
+        Some(ArchiveKind::TarGz) => Ok(Box::new(TarSource::new(file, true))),
+        Some(ArchiveKind::Pdf) => Ok(Box::new(PdfSource::open(file, path)?)),
+        Some(ArchiveKind::Epub) => Ok(Box::new(EpubSource::open(file, path)?)),
+        None if codecs::sniff_file(file) == Some(Format::Image(ImageFormat::Tiff)) => Ok(Box::new(TiffSource::open(file, path)?)),
+        None => Err(anyhow!("Not a supported container: {}", path.display())),
+    }
+}
+
+/// A page path split into the container that holds it and the entry name inside.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualPath {
+    pub container: PathBuf,
+    pub entry: String,
+}
+
+/// Resolve a page path such as `book.cbz/ch1/001.jpg` or `dir/001.jpg`.
+pub fn resolve(path: &Path) -> Option<VirtualPath> {
+    resolve_with(path, |p| p.is_file())
+}
+
+/// Same as [`resolve`], with the filesystem check injected so the walk does not
+/// depend on what is actually on disk.
+pub fn resolve_with(path: &Path, is_file: impl Fn(&Path) -> bool) -> Option<VirtualPath> {
+    if is_file(path) {
+        return Some(VirtualPath {
+            container: path.parent()?.to_path_buf(),
+            entry: path.file_name()?.to_string_lossy().to_string(),
+        });
+    }
+
+    // Walk up until a parent is a file; that file has to be the archive holding the entry.
+    let mut current = path;
+    while let Some(parent) = current.parent() {
+        if is_file(parent) {
+            if !is_container_file(parent) {
+                return None;
+            }
+            let suffix = path.strip_prefix(parent).ok()?;
+            return Some(split_nested(parent.to_path_buf(), normalize_entry_name(&suffix.to_string_lossy())));
+        }
+        current = parent;
+    }
+    None
+}
+
+/// Locate a folder inside an archive: the archive itself (with an empty entry) or
+/// a virtual path below it such as `book.cbz/Vol1`. None for anything on disk.
+pub fn archive_folder(path: &Path) -> Option<VirtualPath> {
+    if path.is_file() {
+        return is_container_file(path).then(|| VirtualPath { container: path.to_path_buf(), entry: String::new() });
+    }
+    let resolved = resolve(path).filter(|resolved| is_container_file(&resolved.container))?;
+    if is_archive(path) {
+        // An archive inside an archive is a root of its own
+        return Some(VirtualPath { container: path.to_path_buf(), entry: String::new() });
+    }
+    Some(resolved)
+}
+
+/// Split archive entries into the sub-folders directly below `prefix` and the files in it.
+/// Folders are derived from the entry names, as many archives store no explicit folder entries.
+pub fn folder_listing(entries: Vec<PageEntry>, prefix: &str) -> (Vec<String>, Vec<PageEntry>) {
+    let prefix = prefix.trim_end_matches('/');
+    let mut folders: Vec<String> = Vec::new();
+    let mut files = Vec::new();
+    for entry in entries {
+        let rest = if prefix.is_empty() {
+            entry.name.as_str()
+        } else {
+            match entry.name.strip_prefix(prefix).and_then(|r| r.strip_prefix('/')) {
+                Some(rest) => rest,
+                None => continue,
+            }
+        };
+        match rest.split_once('/') {
+            Some((folder, _)) => {
+                if !folder.is_empty() && !folders.iter().any(|f| f == folder) {
+                    folders.push(folder.to_string());
+                }
+            }
+            None => files.push(entry),
+        }
+    }
+    (folders, files)
+}
+
+// Entries that pass through another archive (`outer.zip/ch1.cbz/001.jpg`) belong to the
+// innermost archive on the way, which then becomes the container.
+fn split_nested(mut container: PathBuf, mut entry: String) -> VirtualPath {
+    loop {
+        let mut offset = 0;
+        let mut nested_end = None;
+        for part in entry.split('/') {
+            let end = offset + part.len();
+            if end < entry.len() && is_archive(Path::new(part)) {
+                nested_end = Some(end);
+                break;
+            }
+            offset = end + 1;
+        }
+        match nested_end {
+            Some(end) => {
+                container = container.join(&entry[..end]);
+                entry = entry[end + 1..].to_string();
+            }
+            None => return VirtualPath { container, entry },
+        }
+    }
+}
+
+/// Read the bytes of a page, wherever it lives. Archive handles are reused across calls.
+pub fn read_page(path: &Path) -> Result<Vec<u8>> {
+    let resolved = resolve(path).ok_or_else(|| anyhow!("Cannot resolve page: {}", path.display()))?;
+    with_source(&resolved.container, |source| source.read(&resolved.entry))
+}
+
+/// Read at most the first `limit` bytes of a page (see [`PageSource::read_head`]).
+pub fn read_page_head(path: &Path, limit: usize) -> Result<Vec<u8>> {
+    let resolved = resolve(path).ok_or_else(|| anyhow!("Cannot resolve page: {}", path.display()))?;
+    with_source(&resolved.container, |source| source.read_head(&resolved.entry, limit))
+}
+
+/// Reading direction declared by the container at `path`, if it has one.
+pub fn declared_right_to_left(path: &Path) -> Option<bool> {
+    with_source(path, |source| Ok(source.right_to_left())).ok().flatten()
+}
+
+/// Size of a rendered page (see [`PageSource::page_size`]); None for stored images.
+pub fn page_size(path: &Path) -> Option<(u32, u32)> {
+    let resolved = resolve(path)?;
+    with_source(&resolved.container, |source| Ok(source.page_size(&resolved.entry))).ok().flatten()
+}
+
+/// Encode a rendered or converted page as PNG so it travels the same path as stored images.
+/// Fast compression: the bytes are decoded right away and never written out.
+fn encode_png(image: &DynamicImage) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    let encoder = PngEncoder::new_with_quality(Cursor::new(&mut buffer), CompressionType::Fast, FilterType::NoFilter);
+    image.write_with_encoder(encoder)?;
+    Ok(buffer)
+}
+
+// Archives created on Windows may store '\' separators, and tar files often start names
+// with "./"; normalize so entry names line up with the virtual paths built via PathBuf::join.
+fn normalize_entry_name(name: &str) -> String {
+    let name = name.replace('\\', "/");
+    let mut rest = name.as_str();
+    loop {
+        if let Some(stripped) = rest.strip_prefix("./") {
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix('/') {
+            rest = stripped;
+        } else {
+            return rest.to_string();
+        }
+    }
+}
+
+// ZIP and RAR store timestamps in MS-DOS format (local time, 2 second resolution).
+fn dos_time_to_system_time(date: u16, time: u16) -> Option<SystemTime> {
+    let year = 1980 + (date >> 9) as i64;
+    let month = ((date >> 5) & 0x0F) as i64;
+    let day = (date & 0x1F) as i64;
+    if !(1..=12).contains(&month) || day == 0 {
+        return None;
+    }
+    let secs = (time >> 11) as i64 * 3600 + ((time >> 5) & 0x3F) as i64 * 60 + (time & 0x1F) as i64 * 2;
+
+    // Days since the Unix epoch for a proleptic Gregorian date
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    let total = days * 86400 + secs;
+    u64::try_from(total).ok().map(|s| UNIX_EPOCH + Duration::from_secs(s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str) -> PageEntry {
+        PageEntry { name: name.to_string(), size: 0, modified: None }
+    }
+
+    #[test]
+    fn resolves_pages_on_disk() {
+        let resolved = resolve_with(Path::new("/books/001.jpg"), |p| p == Path::new("/books/001.jpg"));
+        assert_eq!(resolved, Some(VirtualPath { container: PathBuf::from("/books"), entry: "001.jpg".into() }));
+        assert_eq!(resolve_with(Path::new("/nowhere/001.jpg"), |_| false), None);
+    }
+
+    #[test]
+    fn resolves_pages_inside_archives() {
+        let is_file = |p: &Path| p == Path::new("/books/a.zip");
+        let resolved = resolve_with(Path::new("/books/a.zip/ch1/001.jpg"), is_file);
+        assert_eq!(resolved, Some(VirtualPath { container: PathBuf::from("/books/a.zip"), entry: "ch1/001.jpg".into() }));
+        // The trailing slash of a folder is not part of the entry
+        let resolved = resolve_with(Path::new("/books/a.zip/ch1/"), is_file);
+        assert_eq!(resolved, Some(VirtualPath { container: PathBuf::from("/books/a.zip"), entry: "ch1".into() }));
+        // A file that is not an archive holds no pages
+        assert_eq!(resolve_with(Path::new("/books/notes.txt/001.jpg"), |p| p == Path::new("/books/notes.txt")), None);
+    }
+
+    #[test]
+    fn splits_nested_archives() {
+        let resolved = resolve_with(Path::new("/books/a.zip/b.cbz/c.png"), |p| p == Path::new("/books/a.zip"));
+        assert_eq!(resolved, Some(VirtualPath { container: PathBuf::from("/books/a.zip/b.cbz"), entry: "c.png".into() }));
+
+        let resolved = split_nested(PathBuf::from("a.zip"), "ch1/b.cbz/vol2.cbr/003.png".into());
+        assert_eq!(resolved, VirtualPath { container: PathBuf::from("a.zip/ch1/b.cbz/vol2.cbr"), entry: "003.png".into() });
+        // A trailing slash after a nested archive means its root
+        let resolved = split_nested(PathBuf::from("a.zip"), "b.cbz/".into());
+        assert_eq!(resolved, VirtualPath { container: PathBuf::from("a.zip/b.cbz"), entry: String::new() });
+        // Without it, the nested archive is an entry of the outer one
+        let resolved = split_nested(PathBuf::from("a.zip"), "b.cbz".into());
+        assert_eq!(resolved, VirtualPath { container: PathBuf::from("a.zip"), entry: "b.cbz".into() });
+    }
+
+    #[test]
+    fn lists_folders_from_entry_names() {
+        let entries = vec![
+            entry("cover.jpg"),
+            entry("Vol1/001.jpg"),
+            entry("Vol1/extra/001.jpg"),
+            entry("Vol1/002.jpg"),
+            entry("Vol2/001.jpg"),
+        ];
+        let names = |files: &[PageEntry]| files.iter().map(|f| f.name.clone()).collect::<Vec<_>>();
+
+        let (folders, files) = folder_listing(entries.clone(), "");
+        assert_eq!(folders, vec!["Vol1", "Vol2"]);
+        assert_eq!(names(&files), vec!["cover.jpg"]);
+
+        let (folders, files) = folder_listing(entries.clone(), "Vol1/");
+        assert_eq!(folders, vec!["extra"]);
+        assert_eq!(names(&files), vec!["Vol1/001.jpg", "Vol1/002.jpg"]);
+
+        let (folders, files) = folder_listing(entries, "Vol1/extra");
+        assert!(folders.is_empty());
+        assert_eq!(names(&files), vec!["Vol1/extra/001.jpg"]);
+    }
+
+    #[test]
+    fn normalizes_entry_names() {
+        assert_eq!(normalize_entry_name(r"ch1\001.jpg"), "ch1/001.jpg");
+        assert_eq!(normalize_entry_name("./ch1/001.jpg"), "ch1/001.jpg");
+        assert_eq!(normalize_entry_name(r".\ch1\001.jpg"), "ch1/001.jpg");
+        assert_eq!(normalize_entry_name("/./001.jpg"), "001.jpg");
+        assert_eq!(normalize_entry_name("ch.1/.hidden.jpg"), "ch.1/.hidden.jpg");
+    }
+
+    #[test]
+    fn converts_dos_timestamps() {
+        // 2024-03-15 13:45:30
+        let date = (44 << 9) | (3 << 5) | 15;
+        let time = (13 << 11) | (45 << 5) | 15;
+        assert_eq!(dos_time_to_system_time(date, time), Some(UNIX_EPOCH + Duration::from_secs(1_710_510_330)));
+        // 1980-01-01, the earliest DOS date
+        assert_eq!(dos_time_to_system_time((1 << 5) | 1, 0), Some(UNIX_EPOCH + Duration::from_secs(315_532_800)));
+        // Unset timestamps are all zero, which is no valid date; neither is month 13
+        assert_eq!(dos_time_to_system_time(0, 0), None);
+        assert_eq!(dos_time_to_system_time((44 << 9) | (13 << 5) | 1, 0), None);
+    }
+}
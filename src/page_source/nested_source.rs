@@ -0,0 +1,58 @@
+use std::io::Write;
+use std::path::Path;
+use anyhow::{anyhow, Result};
+use tempfile::NamedTempFile;
+use super::{PageEntry, PageSource};
+
+/// An archive stored inside another archive (`outer.zip/ch1.cbz`). Its bytes are extracted
+/// to a temporary file since some readers (unrar) can only open real files.
+pub struct NestedSource {
+    // Declared before the file so the reader is closed before the file is deleted
+    inner: Box<dyn PageSource>,
+    _file: NamedTempFile,
+}
+
+impl NestedSource {
+    pub fn open(path: &Path) -> Result<Self> {
+        let resolved = super::resolve(path).ok_or_else(|| anyhow!("Cannot resolve archive: {}", path.display()))?;
+        let mut file = NamedTempFile::new()?;
+        super::with_source(&resolved.container, |source| source.copy_to(&resolved.entry, file.as_file_mut()))?;
+        file.flush()?;
+        let inner = super::open_archive(file.path(), path)?;
+        Ok(Self { inner, _file: file })
+    }
+}
+
+impl PageSource for NestedSource {
+    fn entries(&mut self) -> Result<Vec<PageEntry>> {
+        self.inner.entries()
+    }
+
+    fn read(&mut self, name: &str) -> Result<Vec<u8>> {
+        self.inner.read(name)
+    }
+
+    fn copy_to(&mut self, name: &str, out: &mut dyn Write) -> Result<()> {
+        self.inner.copy_to(name, out)
+    }
+
+    fn read_head(&mut self, name: &str, limit: usize) -> Result<Vec<u8>> {
+        self.inner.read_head(name, limit)
+    }
+
+    fn read_heads(&mut self, names: &[String], limit: usize, visit: &mut dyn FnMut(&str, &[u8]) -> bool) -> Result<()> {
+        self.inner.read_heads(names, limit, visit)
+    }
+
+    fn is_encrypted(&mut self) -> bool {
+        self.inner.is_encrypted()
+    }
+
+    fn page_size(&mut self, name: &str) -> Option<(u32, u32)> {
+        self.inner.page_size(name)
+    }
+
+    fn right_to_left(&mut self) -> Option<bool> {
+        self.inner.right_to_left()
+    }
+}
@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+// Passwords entered (or restored from the DB) for this session, per archive path.
+static PASSWORDS: Lazy<Mutex<HashMap<PathBuf, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Raised by sources that need a password for an archive; callers downcast to prompt the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordError {
+    Required(PathBuf),
+    Incorrect(PathBuf),
+}
+
+impl PasswordError {
+    pub fn archive(&self) -> &Path {
+        match self {
+            PasswordError::Required(path) | PasswordError::Incorrect(path) => path,
+        }
+    }
+}
+
+impl std::fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PasswordError::Required(path) => write!(f, "Password required for {}", path.display()),
+            PasswordError::Incorrect(path) => write!(f, "Incorrect password for {}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for PasswordError {}
+
+pub fn set_password(archive: &Path, password: String) {
+    PASSWORDS.lock().unwrap_or_else(|e| e.into_inner()).insert(archive.to_path_buf(), password);
+}
+
+pub fn password_for(archive: &Path) -> Option<String> {
+    PASSWORDS.lock().unwrap_or_else(|e| e.into_inner()).get(archive).cloned()
+}
+
+pub fn has_password(archive: &Path) -> bool {
+    PASSWORDS.lock().unwrap_or_else(|e| e.into_inner()).contains_key(archive)
+}
@@ -0,0 +1,119 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use pdfium_render::prelude::*;
+use super::{encode_png, password_for, PageEntry, PageSource, PasswordError};
+
+/// Box pages are rendered into until the image view reports its viewport.
+const DEFAULT_RENDER_BOX: (u32, u32) = (1600, 1600);
+
+// PDFium is loaded at runtime, from next to the executable first and then from the
+// system library path. Without it PDFs are listed but cannot be opened.
+static PDFIUM: Lazy<Option<Pdfium>> = Lazy::new(|| {
+    let bundled = std::env::current_exe().ok()
+        .and_then(|exe| exe.parent().map(Pdfium::pdfium_platform_library_name_at_path));
+    let bindings = bundled
+        .and_then(|path| Pdfium::bind_to_library(path).ok())
+        .map(Ok)
+        .unwrap_or_else(Pdfium::bind_to_system_library);
+    match bindings {
+        Ok(bindings) => Some(Pdfium::new(bindings)),
+        Err(e) => {
+            eprintln!("PDFium is not available, PDF files cannot be opened: {}", e);
+            None
+        }
+    }
+});
+
+// Pixel box every page is scaled to fit. Follows the viewport and zoom of the image view.
+static RENDER_BOX: Lazy<Mutex<(u32, u32)>> = Lazy::new(|| Mutex::new(DEFAULT_RENDER_BOX));
+
+/// Set the pixel box pages are rendered into. Returns true when it changed.
+pub fn set_render_box(width: u32, height: u32) -> bool {
+    let mut current = RENDER_BOX.lock().unwrap_or_else(|e| e.into_inner());
+    let changed = *current != (width, height);
+    *current = (width, height);
+    changed
+}
+
+pub fn render_box() -> (u32, u32) {
+    *RENDER_BOX.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A PDF document whose pages are listed as `001.png`, `002.png`, ... and rendered on read.
+pub struct PdfSource {
+    path: PathBuf,
+    document: PdfDocument<'static>,
+    modified: Option<SystemTime>,
+}
+
+impl PdfSource {
+    pub fn open(file: &Path, path: &Path) -> Result<Self> {
+        let pdfium = PDFIUM.as_ref().ok_or_else(|| {
+            anyhow!("PDF support needs the PDFium library ({})", Pdfium::pdfium_platform_library_name().to_string_lossy())
+        })?;
+        let password = password_for(path);
+        let document = match pdfium.load_pdf_from_file(file, password.as_deref()) {
+            Ok(document) => document,
+            Err(PdfiumError::PdfiumLibraryInternalError(PdfiumInternalError::PasswordError)) => {
+                return Err(match password {
+                    Some(_) => PasswordError::Incorrect(path.to_path_buf()),
+                    None => PasswordError::Required(path.to_path_buf()),
+                }.into());
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let modified = std::fs::metadata(file).and_then(|m| m.modified()).ok();
+        Ok(Self { path: path.to_path_buf(), document, modified })
+    }
+
+    fn page(&self, name: &str) -> Result<PdfPage<'_>> {
+        let index = name.strip_suffix(".png")
+            .and_then(|n| n.parse::<PdfPageIndex>().ok())
+            .and_then(|n| n.checked_sub(1))
+            .ok_or_else(|| anyhow!("Entry not found in {}: {}", self.path.display(), name))?;
+        Ok(self.document.pages().get(index)?)
+    }
+}
+
+impl PageSource for PdfSource {
+    fn entries(&mut self) -> Result<Vec<PageEntry>> {
+        let count = self.document.pages().len();
+        // Zero padded so that plain name sorting keeps the page order
+        let width = count.to_string().len();
+        let entries = (1..=count)
+            .map(|n| PageEntry {
+                name: format!("{:0width$}.png", n, width = width),
+                size: 0,
+                modified: self.modified,
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    fn read(&mut self, name: &str) -> Result<Vec<u8>> {
+        let page = self.page(name)?;
+        let config = PdfRenderConfig::new().scale_page_by_factor(render_scale(&page));
+        let image = page.render_with_config(&config)?.as_image()?;
+        encode_png(&image)
+    }
+
+    fn page_size(&mut self, name: &str) -> Option<(u32, u32)> {
+        let page = self.page(name).ok()?;
+        let scale = render_scale(&page);
+        Some(((page.width().value * scale).round() as u32, (page.height().value * scale).round() as u32))
+    }
+}
+
+/// Pixels per point that fit `page` into the render box, as used by both
+/// `read` and `page_size` so the two always agree.
+fn render_scale(page: &PdfPage) -> f32 {
+    let (width, height) = render_box();
+    let (page_width, page_height) = (page.width().value, page.height().value);
+    if page_width <= 0.0 || page_height <= 0.0 {
+        return 1.0;
+    }
+    (width as f32 / page_width).min(height as f32 / page_height)
+}
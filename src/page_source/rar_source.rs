@@ -0,0 +1,72 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Result};
+use super::{dos_time_to_system_time, normalize_entry_name, PageEntry, PageSource};
+
+/// RAR v4 and v5 are both handled by the bundled unrar library,
+/// so no external `unrar` binary is needed at runtime.
+pub struct RarSource {
+    path: PathBuf,
+    // unrar only offers sequential access, so the listing is the only thing worth keeping.
+    entries: Option<Vec<PageEntry>>,
+}
+
+impl RarSource {
+    pub fn new(path: &Path) -> Self {
+        Self { path: path.to_path_buf(), entries: None }
+    }
+}
+
+impl PageSource for RarSource {
+    fn entries(&mut self) -> Result<Vec<PageEntry>> {
+        if let Some(entries) = &self.entries {
+            return Ok(entries.clone());
+        }
+        let archive = unrar::Archive::new(&self.path).open_for_listing()?;
+        let mut entries = Vec::new();
+        for header in archive {
+            let header = header?;
+            if header.is_file() {
+                entries.push(PageEntry {
+                    name: normalize_entry_name(&header.filename.to_string_lossy()),
+                    size: header.unpacked_size,
+                    modified: dos_time_to_system_time((header.file_time >> 16) as u16, header.file_time as u16),
+                });
+            }
+        }
+        self.entries = Some(entries.clone());
+        Ok(entries)
+    }
+
+    fn read(&mut self, name: &str) -> Result<Vec<u8>> {
+        let mut archive = unrar::Archive::new(&self.path).open_for_processing()?;
+        while let Some(header) = archive.read_header()? {
+            if header.entry().is_file() && normalize_entry_name(&header.entry().filename.to_string_lossy()) == name {
+                let (data, _) = header.read()?;
+                return Ok(data);
+            }
+            archive = header.skip()?;
+        }
+        Err(anyhow!("Entry not found in {}: {}", self.path.display(), name))
+    }
+
+    fn read_heads(&mut self, names: &[String], limit: usize, visit: &mut dyn FnMut(&str, &[u8]) -> bool) -> Result<()> {
+        let wanted: HashSet<&str> = names.iter().map(|n| n.as_str()).collect();
+        let mut archive = unrar::Archive::new(&self.path).open_for_processing()?;
+        while let Some(header) = archive.read_header()? {
+            let name = normalize_entry_name(&header.entry().filename.to_string_lossy());
+            if !header.entry().is_file() || !wanted.contains(name.as_str()) {
+                archive = header.skip()?;
+                continue;
+            }
+            // unrar only extracts whole entries
+            let (mut data, next) = header.read()?;
+            data.truncate(limit);
+            if !visit(&name, &data) {
+                break;
+            }
+            archive = next;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::{HashSet, VecDeque};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+use anyhow::{anyhow, Result};
+use sevenz_rust::{Archive, BlockDecoder, SevenZArchiveEntry};
+use super::{normalize_entry_name, PageEntry, PageSource};
+
+// Seconds between 1601-01-01 (Windows FILETIME epoch) and 1970-01-01
+const FILETIME_UNIX_OFFSET: u64 = 11_644_473_600;
+
+/// Bytes of decoded entries kept from the last block read. Entries of a solid block can
+/// only be reached by decoding everything before them, so paging through one would
+/// otherwise decode the block again for every page.
+const READ_AHEAD_BYTES: usize = 32 * 1024 * 1024;
+
+pub struct SevenZipSource {
+    path: PathBuf,
+    // Parsed header, kept between reads
+    archive: Archive,
+    file: std::fs::File,
+    // Recently decoded entries, oldest first, together taking at most READ_AHEAD_BYTES
+    decoded: VecDeque<(String, Vec<u8>)>,
+}
+
+impl SevenZipSource {
+    pub fn open(path: &Path) -> Result<Self> {
+        let archive = Archive::open(path)?;
+        let file = std::fs::File::open(path)?;
+        Ok(Self { path: path.to_path_buf(), archive, file, decoded: VecDeque::new() })
+    }
+
+    /// The block holding entry `name`, or None for an empty entry that has no data.
+    fn block_of(&self, name: &str) -> Result<Option<usize>> {
+        let index = self.archive.files.iter()
+            .position(|entry| !entry.is_directory && normalize_entry_name(&entry.name) == name)
+            .ok_or_else(|| anyhow!("Entry not found in {}: {}", self.path.display(), name))?;
+        Ok(self.archive.stream_map.file_folder_index[index])
+    }
+
+    /// Decode block `block` from its start, passing each entry to `f` until it returns false.
+    /// Entries `f` does not read to the end are read through so the next one lines up.
+    fn for_each_in_block(
+        &mut self,
+        block: usize,
+        mut f: impl FnMut(&SevenZArchiveEntry, &mut dyn Read) -> Result<bool, sevenz_rust::Error>,
+    ) -> Result<()> {
+        BlockDecoder::new(block, &self.archive, &[], &mut self.file).for_each_entries(&mut |entry, data| {
+            if !f(entry, data)? {
+                return Ok(false);
+            }
+            std::io::copy(data, &mut std::io::sink())?;
+            Ok(true)
+        })?;
+        Ok(())
+    }
+
+    /// Keep a decoded entry, dropping the oldest ones once over the budget.
+    fn remember(&mut self, name: String, data: Vec<u8>) {
+        self.decoded.retain(|(n, _)| *n != name);
+        self.decoded.push_back((name, data));
+        let mut total: usize = self.decoded.iter().map(|(_, d)| d.len()).sum();
+        while total > READ_AHEAD_BYTES && self.decoded.len() > 1 {
+            if let Some((_, evicted)) = self.decoded.pop_front() {
+                total -= evicted.len();
+            }
+        }
+    }
+}
+
+impl PageSource for SevenZipSource {
+    fn entries(&mut self) -> Result<Vec<PageEntry>> {
+        let entries = self.archive.files.iter()
+            .filter(|entry| entry.has_stream && !entry.is_directory)
+            .map(|entry| PageEntry {
+                name: normalize_entry_name(&entry.name),
+                size: entry.size,
+                modified: if entry.has_last_modified_date {
+                    (entry.last_modified_date.to_raw() / 10_000_000)
+                        .checked_sub(FILETIME_UNIX_OFFSET)
+                        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+                } else {
+                    None
+                },
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    fn read(&mut self, name: &str) -> Result<Vec<u8>> {
+        if let Some((_, data)) = self.decoded.iter().find(|(n, _)| n == name) {
+            return Ok(data.clone());
+        }
+        let Some(block) = self.block_of(name)? else { return Ok(Vec::new()); };
+
+        // Decode up to the entry, then keep going while the entries after it fit the budget
+        let mut found = None;
+        let mut ahead = Vec::new();
+        let mut ahead_bytes = 0;
+        self.for_each_in_block(block, |entry, data| {
+            if entry.is_directory {
+                return Ok(true);
+            }
+            let entry_name = normalize_entry_name(&entry.name);
+            if found.is_none() {
+                if entry_name == name {
+                    let mut buffer = Vec::with_capacity(entry.size as usize);
+                    data.read_to_end(&mut buffer)?;
+                    found = Some(buffer);
+                }
+                return Ok(true);
+            }
+            ahead_bytes += entry.size as usize;
+            if ahead_bytes > READ_AHEAD_BYTES {
+                return Ok(false);
+            }
+            let mut buffer = Vec::with_capacity(entry.size as usize);
+            data.read_to_end(&mut buffer)?;
+            ahead.push((entry_name, buffer));
+            Ok(true)
+        })?;
+
+        let data = found.ok_or_else(|| anyhow!("Entry not found in {}: {}", self.path.display(), name))?;
+        self.remember(name.to_string(), data.clone());
+        for (entry_name, buffer) in ahead {
+            self.remember(entry_name, buffer);
+        }
+        Ok(data)
+    }
+
+    fn copy_to(&mut self, name: &str, out: &mut dyn Write) -> Result<()> {
+        let Some(block) = self.block_of(name)? else { return Ok(()); };
+        let mut found = false;
+        self.for_each_in_block(block, |entry, data| {
+            if entry.is_directory || normalize_entry_name(&entry.name) != name {
+                return Ok(true);
+            }
+            std::io::copy(data, out)?;
+            found = true;
+            Ok(false)
+        })?;
+        if !found {
+            return Err(anyhow!("Entry not found in {}: {}", self.path.display(), name));
+        }
+        Ok(())
+    }
+
+    fn read_heads(&mut self, names: &[String], limit: usize, visit: &mut dyn FnMut(&str, &[u8]) -> bool) -> Result<()> {
+        let wanted: HashSet<&str> = names.iter().map(|n| n.as_str()).collect();
+        // Only blocks holding a wanted entry are decoded, each in one pass
+        let mut blocks: Vec<usize> = self.archive.files.iter()
+            .zip(&self.archive.stream_map.file_folder_index)
+            .filter(|(entry, _)| !entry.is_directory && wanted.contains(normalize_entry_name(&entry.name).as_str()))
+            .filter_map(|(_, block)| *block)
+            .collect();
+        blocks.sort_unstable();
+        blocks.dedup();
+        let mut stopped = false;
+        for block in blocks {
+            self.for_each_in_block(block, |entry, data| {
+                let name = normalize_entry_name(&entry.name);
+                if entry.is_directory || !wanted.contains(name.as_str()) {
+                    return Ok(true);
+                }
+                let mut head = Vec::new();
+                data.take(limit as u64).read_to_end(&mut head)?;
+                stopped = !visit(&name, &head);
+                Ok(!stopped)
+            })?;
+            if stopped {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
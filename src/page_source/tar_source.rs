@@ -0,0 +1,130 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+use anyhow::{anyhow, Result};
+use super::{normalize_entry_name, PageEntry, PageSource};
+
+/// Plain or gzip-compressed TAR. TAR has no central directory, so one is built on the
+/// first listing: plain TARs can then seek straight to an entry, gzip ones still scan.
+pub struct TarSource {
+    path: PathBuf,
+    gzip: bool,
+    entries: Option<Vec<PageEntry>>,
+    // entry name -> (data offset, size) within the uncompressed stream
+    index: HashMap<String, (u64, u64)>,
+}
+
+impl TarSource {
+    pub fn new(path: &Path, gzip: bool) -> Self {
+        Self { path: path.to_path_buf(), gzip, entries: None, index: HashMap::new() }
+    }
+
+    fn open_archive(&self) -> Result<tar::Archive<Box<dyn Read>>> {
+        let file = std::io::BufReader::new(std::fs::File::open(&self.path)?);
+        let reader: Box<dyn Read> = if self.gzip {
+            Box::new(flate2::read::GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+        Ok(tar::Archive::new(reader))
+    }
+
+    fn read_entry(&mut self, name: &str, limit: u64) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.copy_entry(name, limit, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Write up to `limit` bytes of an entry to `out`: by offset in plain TARs, by scanning gzip ones.
+    fn copy_entry(&mut self, name: &str, limit: u64, out: &mut dyn Write) -> Result<()> {
+        if !self.gzip {
+            if self.entries.is_none() {
+                self.entries()?;
+            }
+            if let Some(&(offset, size)) = self.index.get(name) {
+                let mut file = std::fs::File::open(&self.path)?;
+                file.seek(SeekFrom::Start(offset))?;
+                std::io::copy(&mut file.take(size.min(limit)), out)?;
+                return Ok(());
+            }
+        }
+
+        let mut archive = self.open_archive()?;
+        for entry in archive.entries()? {
+            let entry = entry?;
+            if entry.header().entry_type().is_file() && normalize_entry_name(&entry.path()?.to_string_lossy()) == name {
+                std::io::copy(&mut entry.take(limit), out)?;
+                return Ok(());
+            }
+        }
+        Err(anyhow!("Entry not found in {}: {}", self.path.display(), name))
+    }
+}
+
+impl PageSource for TarSource {
+    fn entries(&mut self) -> Result<Vec<PageEntry>> {
+        if let Some(entries) = &self.entries {
+            return Ok(entries.clone());
+        }
+        let mut archive = self.open_archive()?;
+        let mut entries = Vec::new();
+        let mut index = HashMap::new();
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let header = entry.header();
+            if header.entry_type().is_file() {
+                let name = normalize_entry_name(&entry.path()?.to_string_lossy());
+                index.insert(name.clone(), (entry.raw_file_position(), entry.size()));
+                entries.push(PageEntry {
+                    name,
+                    size: header.size().unwrap_or(0),
+                    modified: header.mtime().ok().map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
+                });
+            }
+        }
+        self.entries = Some(entries.clone());
+        self.index = index;
+        Ok(entries)
+    }
+
+    fn read(&mut self, name: &str) -> Result<Vec<u8>> {
+        self.read_entry(name, u64::MAX)
+    }
+
+    fn copy_to(&mut self, name: &str, out: &mut dyn Write) -> Result<()> {
+        self.copy_entry(name, u64::MAX, out)
+    }
+
+    fn read_head(&mut self, name: &str, limit: usize) -> Result<Vec<u8>> {
+        self.read_entry(name, limit as u64)
+    }
+
+    fn read_heads(&mut self, names: &[String], limit: usize, visit: &mut dyn FnMut(&str, &[u8]) -> bool) -> Result<()> {
+        if !self.gzip {
+            // Entries can be reached directly
+            for name in names {
+                let Ok(head) = self.read_entry(name, limit as u64) else { continue; };
+                if !visit(name, &head) {
+                    break;
+                }
+            }
+            return Ok(());
+        }
+        let wanted: HashSet<&str> = names.iter().map(|n| n.as_str()).collect();
+        let mut archive = self.open_archive()?;
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let name = normalize_entry_name(&entry.path()?.to_string_lossy());
+            if !entry.header().entry_type().is_file() || !wanted.contains(name.as_str()) {
+                continue;
+            }
+            let mut head = Vec::new();
+            entry.take(limit as u64).read_to_end(&mut head)?;
+            if !visit(&name, &head) {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use image::{DynamicImage, RgbaImage};
+use tiff::decoder::{Decoder, DecodingResult};
+use tiff::ColorType;
+use super::{encode_png, FileStamp, PageEntry, PageSource};
+
+/// Upper bound on remembered page counts; the map starts over once it is reached.
+const MAX_REMEMBERED_TIFFS: usize = 4096;
+
+// Whether a TIFF file holds several pages, valid while its stamp matches. Directory
+// listings ask for every TIFF in them each time.
+static MULTIPAGE: Lazy<Mutex<HashMap<PathBuf, (FileStamp, bool)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A multi-page TIFF whose pages are listed as `001.png`, `002.png`, ... and decoded on read.
+pub struct TiffSource {
+    path: PathBuf,
+    decoder: Decoder<BufReader<File>>,
+    pages: usize,
+    modified: Option<SystemTime>,
+}
+
+impl TiffSource {
+    pub fn open(file: &Path, path: &Path) -> Result<Self> {
+        let mut decoder = Decoder::new(BufReader::new(File::open(file)?))?;
+        let mut pages = 1;
+        while decoder.more_images() {
+            decoder.next_image()?;
+            pages += 1;
+        }
+        let modified = std::fs::metadata(file).and_then(|m| m.modified()).ok();
+        Ok(Self { path: path.to_path_buf(), decoder, pages, modified })
+    }
+
+    fn seek(&mut self, name: &str) -> Result<()> {
+        let index = name.strip_suffix(".png")
+            .and_then(|n| n.parse::<usize>().ok())
+            .and_then(|n| n.checked_sub(1))
+            .filter(|&n| n < self.pages)
+            .ok_or_else(|| anyhow!("Entry not found in {}: {}", self.path.display(), name))?;
+        self.decoder.seek_to_image(index)?;
+        Ok(())
+    }
+}
+
+impl PageSource for TiffSource {
+    fn entries(&mut self) -> Result<Vec<PageEntry>> {
+        // Zero padded so that plain name sorting keeps the page order
+        let width = self.pages.to_string().len();
+        let entries = (1..=self.pages)
+            .map(|n| PageEntry {
+                name: format!("{:0width$}.png", n, width = width),
+                size: 0,
+                modified: self.modified,
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    fn read(&mut self, name: &str) -> Result<Vec<u8>> {
+        self.seek(name)?;
+        let (width, height) = self.decoder.dimensions()?;
+        let color = self.decoder.colortype()?;
+        let image = to_rgba(width, height, color, self.decoder.read_image()?)?;
+        encode_png(&DynamicImage::ImageRgba8(image))
+    }
+
+    fn page_size(&mut self, name: &str) -> Option<(u32, u32)> {
+        self.seek(name).ok()?;
+        self.decoder.dimensions().ok()
+    }
+}
+
+/// True for a `.tif`/`.tiff` file on disk holding more than one page. Files are only
+/// parsed the first time they are seen and again after they change.
+pub fn is_multipage_tiff(path: &Path) -> bool {
+    let is_tiff_name = path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("tif") || ext.eq_ignore_ascii_case("tiff"));
+    if !is_tiff_name || !path.is_file() {
+        return false;
+    }
+    let Ok(stamp) = FileStamp::of(path) else { return false; };
+    let remembered = MULTIPAGE.lock().unwrap_or_else(|e| e.into_inner()).get(path).copied();
+    if let Some((known_stamp, multipage)) = remembered {
+        if known_stamp == stamp {
+            return multipage;
+        }
+    }
+    let multipage = File::open(path)
+        .is_ok_and(|file| Decoder::new(file).is_ok_and(|decoder| decoder.more_images()));
+    let mut known = MULTIPAGE.lock().unwrap_or_else(|e| e.into_inner());
+    if known.len() >= MAX_REMEMBERED_TIFFS {
+        known.clear();
+    }
+    known.insert(path.to_path_buf(), (stamp, multipage));
+    multipage
+}
+
+fn to_rgba(width: u32, height: u32, color: ColorType, data: DecodingResult) -> Result<RgbaImage> {
+    // Deeper samples are cut down to 8 bits
+    let samples: Vec<u8> = match data {
+        DecodingResult::U8(samples) => samples,
+        DecodingResult::U16(samples) => samples.into_iter().map(|s| (s >> 8) as u8).collect(),
+        _ => return Err(anyhow!("Unsupported TIFF sample format")),
+    };
+    let rgba: Vec<u8> = match color {
+        ColorType::Gray(1) => {
+            // Bilevel scans: one bit per pixel, rows padded to whole bytes
+            let row_bytes = (width as usize).div_ceil(8);
+            samples.chunks(row_bytes)
+                .flat_map(|row| (0..width as usize).map(move |x| if row[x / 8] & (0x80 >> (x % 8)) != 0 { 255 } else { 0 }))
+                .flat_map(|l| [l, l, l, 255])
+                .collect()
+        }
+        ColorType::Gray(_) => samples.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        ColorType::GrayA(_) => samples.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        ColorType::RGB(_) => samples.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        ColorType::RGBA(_) => samples,
+        ColorType::CMYK(_) => samples.chunks_exact(4)
+            .flat_map(|p| {
+                let k = 255 - p[3] as u32;
+                let channel = |c: u8| ((255 - c as u32) * k / 255) as u8;
+                [channel(p[0]), channel(p[1]), channel(p[2]), 255]
+            })
+            .collect(),
+        other => return Err(anyhow!("Unsupported TIFF color type: {:?}", other)),
+    };
+    RgbaImage::from_raw(width, height, rgba).ok_or_else(|| anyhow!("TIFF page size mismatch"))
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Result};
+use zip::result::ZipError;
+use super::encoding::decode_names;
+use super::{dos_time_to_system_time, name_encoding, normalize_entry_name, password_for, NameEncoding, PageEntry, PageSource, PasswordError};
+
+pub struct ZipSource {
+    path: PathBuf,
+    archive: zip::ZipArchive<std::fs::File>,
+    // Whether each entry's name is flagged as UTF-8, which the zip crate does not expose
+    utf8_names: Vec<bool>,
+    // Decoded entry name -> index, built for the encoding it was decoded with.
+    // Entries are always read by index so legacy names never have to round-trip.
+    index: Option<(NameEncoding, HashMap<String, usize>)>,
+}
+
+impl ZipSource {
+    pub fn open(file: &Path, path: &Path) -> Result<Self> {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(file)?)?;
+        let utf8_names = utf8_flags(&mut archive, file)?;
+        Ok(Self { path: path.to_path_buf(), archive, utf8_names, index: None })
+    }
+
+    fn decoded_names(&mut self, encoding: NameEncoding) -> Vec<String> {
+        let raw_names: Vec<Vec<u8>> = (0..self.archive.len())
+            .map(|i| self.archive.by_index_raw(i).map(|e| e.name_raw().to_vec()).unwrap_or_default())
+            .collect();
+        let raw_refs: Vec<(&[u8], bool)> = raw_names.iter()
+            .enumerate()
+            .map(|(i, n)| (n.as_slice(), self.utf8_names.get(i).copied().unwrap_or(false)))
+            .collect();
+        decode_names(&raw_refs, encoding).iter().map(|n| normalize_entry_name(n)).collect()
+    }
+
+    fn index_of(&mut self, name: &str) -> Result<usize> {
+        let encoding = name_encoding(&self.path);
+        if self.index.as_ref().map(|(e, _)| *e) != Some(encoding) {
+            let names = self.decoded_names(encoding);
+            let map = names.into_iter().enumerate().map(|(i, n)| (n, i)).collect();
+            self.index = Some((encoding, map));
+        }
+        self.index.as_ref()
+            .and_then(|(_, map)| map.get(name).copied())
+            .ok_or_else(|| anyhow!("Entry not found in {}: {}", self.path.display(), name))
+    }
+
+    /// Read up to `limit` bytes of an entry, decrypting it with the stored password.
+    fn read_entry(&mut self, name: &str, limit: u64) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.copy_entry(name, limit, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Write up to `limit` bytes of an entry to `out`, decrypting it with the stored password.
+    fn copy_entry(&mut self, name: &str, limit: u64, out: &mut dyn Write) -> Result<()> {
+        let index = self.index_of(name)?;
+        let password = password_for(&self.path);
+        let entry = match &password {
+            Some(password) => self.archive.by_index_decrypt(index, password.as_bytes()),
+            None => self.archive.by_index(index),
+        };
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(ZipError::UnsupportedArchive(msg)) if msg == ZipError::PASSWORD_REQUIRED => {
+                return Err(PasswordError::Required(self.path.clone()).into());
+            }
+            Err(ZipError::InvalidPassword) => return Err(PasswordError::Incorrect(self.path.clone()).into()),
+            Err(e) => return Err(e.into()),
+        };
+        let encrypted = entry.encrypted();
+        if let Err(e) = std::io::copy(&mut entry.take(limit), out) {
+            // ZipCrypto's header check lets roughly 1 in 256 wrong passwords through;
+            // those only show up as a checksum failure once the data is read.
+            if encrypted {
+                return Err(PasswordError::Incorrect(self.path.clone()).into());
+            }
+            return Err(e.into());
+        }
+        Ok(())
+    }
+}
+
+impl PageSource for ZipSource {
+    fn entries(&mut self) -> Result<Vec<PageEntry>> {
+        let names = self.decoded_names(name_encoding(&self.path));
+        let mut entries = Vec::new();
+        for (i, name) in names.into_iter().enumerate() {
+            if let Ok(entry) = self.archive.by_index_raw(i) {
+                if entry.is_file() {
+                    entries.push(PageEntry {
+                        name,
+                        size: entry.size(),
+                        modified: entry.last_modified().and_then(|t| dos_time_to_system_time(t.datepart(), t.timepart())),
+                    });
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    fn read(&mut self, name: &str) -> Result<Vec<u8>> {
+        self.read_entry(name, u64::MAX)
+    }
+
+    fn read_head(&mut self, name: &str, limit: usize) -> Result<Vec<u8>> {
+        self.read_entry(name, limit as u64)
+    }
+
+    fn copy_to(&mut self, name: &str, out: &mut dyn Write) -> Result<()> {
+        self.copy_entry(name, u64::MAX, out)
+    }
+
+    fn is_encrypted(&mut self) -> bool {
+        (0..self.archive.len()).any(|i| self.archive.by_index_raw(i).is_ok_and(|e| e.encrypted()))
+    }
+}
+
+/// General purpose bit 11 of every entry, read from its central directory header.
+fn utf8_flags(archive: &mut zip::ZipArchive<std::fs::File>, file: &Path) -> Result<Vec<bool>> {
+    let mut file = std::fs::File::open(file)?;
+    (0..archive.len())
+        .map(|i| {
+            // Signature, version made by and version needed come before the flags
+            let offset = archive.by_index_raw(i)?.central_header_start() + 8;
+            file.seek(SeekFrom::Start(offset))?;
+            let mut flags = [0u8; 2];
+            file.read_exact(&mut flags)?;
+            Ok(u16::from_le_bytes(flags) & (1 << 11) != 0)
+        })
+        .collect()
+}
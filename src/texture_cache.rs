@@ -0,0 +1,77 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use gtk4::gdk;
+use gtk4::prelude::*;
+
+/// LRU cache of decoded page textures, bounded by an estimate of their pixel memory.
+#[derive(Debug)]
+pub struct TextureCache {
+    // Most recently used first
+    entries: VecDeque<(PathBuf, gdk::Texture, usize)>,
+    used_bytes: usize,
+    budget_bytes: usize,
+}
+
+impl TextureCache {
+    pub fn new(budget_bytes: usize) -> Self {
+        Self { entries: VecDeque::new(), used_bytes: 0, budget_bytes }
+    }
+
+    pub fn set_budget(&mut self, budget_bytes: usize) {
+        self.budget_bytes = budget_bytes;
+        self.evict();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.budget_bytes > 0
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.entries.iter().any(|(p, _, _)| p == path)
+    }
+
+    pub fn get(&mut self, path: &Path) -> Option<gdk::Texture> {
+        let idx = self.entries.iter().position(|(p, _, _)| p == path)?;
+        let entry = self.entries.remove(idx)?;
+        let texture = entry.1.clone();
+        self.entries.push_front(entry);
+        Some(texture)
+    }
+
+    pub fn insert(&mut self, path: PathBuf, texture: gdk::Texture) {
+        let size = texture.width().max(0) as usize * texture.height().max(0) as usize * 4;
+        if size > self.budget_bytes {
+            return;
+        }
+        if let Some(idx) = self.entries.iter().position(|(p, _, _)| *p == path) {
+            if let Some((_, _, old_size)) = self.entries.remove(idx) {
+                self.used_bytes -= old_size;
+            }
+        }
+        self.entries.push_front((path, texture, size));
+        self.used_bytes += size;
+        self.evict();
+    }
+
+    /// Drop every texture whose path does not satisfy `keep`.
+    pub fn retain(&mut self, keep: impl Fn(&Path) -> bool) {
+        let mut used_bytes = 0;
+        self.entries.retain(|(p, _, size)| {
+            let kept = keep(p);
+            if kept {
+                used_bytes += size;
+            }
+            kept
+        });
+        self.used_bytes = used_bytes;
+    }
+
+    fn evict(&mut self) {
+        while self.used_bytes > self.budget_bytes {
+            match self.entries.pop_back() {
+                Some((_, _, size)) => self.used_bytes -= size,
+                None => break,
+            }
+        }
+    }
+}
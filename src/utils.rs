@@ -1,6 +1,6 @@
 use std::path::Path;
 
-pub const SUPPORTED_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "bmp"];
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "bmp", "avif", "jxl", "heic", "heif", "tif", "tiff", "tga", "qoi", "ico", "psd"];
 
 pub fn is_supported_image(path: &Path) -> bool {
     if let Some(ext) = path.extension().and_then(|s| s.to_str()).map(|s| s.to_lowercase()) {
@@ -10,31 +10,11 @@ pub fn is_supported_image(path: &Path) -> bool {
     }
 }
 
-pub fn is_apng(path: &Path) -> bool {
-    if let Ok(mut file) = std::fs::File::open(path) {
-        use std::io::{Read, Seek};
-        let mut header = [0u8; 8];
-        if file.read_exact(&mut header).is_err() { return false; }
-        // PNG Signature: 89 50 4E 47 0D 0A 1A 0A
-        if header != [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A] { return false; }
-
-        loop {
-            let mut length_bytes = [0u8; 4];
-            if file.read_exact(&mut length_bytes).is_err() { break; }
-            let length = u32::from_be_bytes(length_bytes) as u64;
-
-            let mut type_bytes = [0u8; 4];
-            if file.read_exact(&mut type_bytes).is_err() { break; }
-            
-            // Chunk Type
-            if &type_bytes == b"acTL" { return true; }
-            if &type_bytes == b"IDAT" { return false; } // acTL must come before IDAT
-
-            // Skip data + crc (4 bytes)
-            if file.seek(std::io::SeekFrom::Current((length + 4) as i64)).is_err() { break; }
-        }
-    }
-    false
+/// Like [`is_supported_image`], but a file on disk without an extension is also
+/// accepted when its content is a known image format. Only those are opened, so
+/// listing a large folder does not read every file in it.
+pub fn is_image_file(path: &Path) -> bool {
+    is_supported_image(path) || (path.extension().is_none() && path.is_file() && crate::codecs::sniff_file(path).is_some())
 }
 
 pub fn is_apng_bytes(bytes: &[u8]) -> bool {
@@ -55,16 +35,6 @@ pub fn is_apng_bytes(bytes: &[u8]) -> bool {
     false
 }
 
-pub fn is_animated_webp(path: &Path) -> bool {
-    if let Ok(file) = std::fs::File::open(path) {
-        let buf_reader = std::io::BufReader::new(file);
-        if let Ok(decoder) = image::codecs::webp::WebPDecoder::new(buf_reader) {
-             return decoder.has_animation();
-        }
-    }
-    false
-}
-
 pub fn is_animated_webp_bytes(bytes: &[u8]) -> bool {
     let cursor = std::io::Cursor::new(bytes);
     if let Ok(decoder) = image::codecs::webp::WebPDecoder::new(cursor) {